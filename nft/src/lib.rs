@@ -15,18 +15,31 @@ NOTES:
   - To prevent the deployed contract from being modified or deleted, it should not have any access
     keys on its account.
 */
+use near_contract_standards::non_fungible_token::approval::NonFungibleTokenApproval;
+use near_contract_standards::non_fungible_token::core::{
+    NonFungibleTokenCore, NonFungibleTokenResolver,
+};
+use near_contract_standards::non_fungible_token::enumeration::NonFungibleTokenEnumeration;
+use near_contract_standards::non_fungible_token::events::{NftBurn, NftMint, NftTransfer};
 use near_contract_standards::non_fungible_token::metadata::{
     NFTContractMetadata, NonFungibleTokenMetadataProvider, TokenMetadata, NFT_METADATA_SPEC,
 };
-use near_contract_standards::non_fungible_token::NonFungibleToken;
+use near_contract_standards::non_fungible_token::{
+    refund_approved_account_ids, refund_deposit, refund_deposit_to_account, NonFungibleToken,
+};
 use near_contract_standards::non_fungible_token::{Token, TokenId};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::LazyOption;
-use near_sdk::serde::Serialize;
+use near_sdk::collections::{
+    LazyOption, LookupMap, LookupSet, TreeMap, UnorderedMap, UnorderedSet, Vector,
+};
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::{json, Value};
 use near_sdk::{
-    env, log, near_bindgen, AccountId, BorshStorageKey, PanicOnDefault, Promise,
-    PromiseOrValue,
+    assert_one_yocto, env, ext_contract, log, near_bindgen, require, AccountId, BorshStorageKey,
+    Gas, PanicOnDefault, Promise, PromiseOrValue,
 };
+use std::collections::{HashMap, HashSet};
 #[derive(BorshDeserialize, BorshSerialize, Serialize)]
 pub enum Status {
     All,
@@ -34,6 +47,115 @@ pub enum Status {
     None,
 }
 
+/// Configuration accepted by `bulk_configure`. Only the fields that are `Some` are applied.
+#[derive(Serialize, Deserialize)]
+pub struct PartialConfig {
+    pub approval_status: Option<String>,
+    pub whitelist_window: Option<(u64, u64)>,
+    pub max_attached_deposit: Option<U128>,
+    pub require_reference_hash: Option<bool>,
+    pub approval_rate_limit: Option<(u64, u32)>,
+    pub content_rating: Option<String>,
+}
+
+/// What `account_id` needs to mint a token right now, returned by `mint_requirements`.
+#[derive(Serialize)]
+pub struct MintRequirements {
+    pub required_deposit: U128,
+    pub allowed: bool,
+    pub reason: Option<String>,
+}
+
+/// Point-in-time supply counters, returned by `supply_snapshot`.
+#[derive(Serialize)]
+pub struct SupplySnapshot {
+    pub total_minted: u64,
+    pub total_burned: u64,
+    pub current_supply: u64,
+    pub block_height: u64,
+}
+
+/// Compliance features and role accounts, returned by `get_compliance_config`. Escrowed mints
+/// through `nft_mint_pending` are always available; the contract has no receiver allowlist and
+/// no storage registration requirement.
+#[derive(Serialize)]
+pub struct ComplianceConfig {
+    pub kyc_escrow: bool,
+    pub receiver_allowlist: bool,
+    pub require_registered: bool,
+    pub compliance_accounts: Vec<AccountId>,
+}
+
+/// Holder balances recorded page by page by `snapshot_holders`.
+#[derive(BorshDeserialize, BorshSerialize)]
+struct HolderSnapshot {
+    holders: UnorderedMap<AccountId, u64>,
+    /// Last token id read; the next page resumes after it.
+    cursor: Option<TokenId>,
+    tokens_read: u64,
+}
+
+/// State layout of the original contract. `new` starts from it and `migrate` reads it back.
+#[derive(BorshDeserialize, BorshSerialize)]
+struct BaseState {
+    tokens: NonFungibleToken,
+    metadata: LazyOption<NFTContractMetadata>,
+    mint_approval_status: Status,
+    whitelist_accounts: Vec<AccountId>,
+}
+
+/// Aggregate collection stats for a homepage, returned by `get_dashboard`. The collection has
+/// no supply cap, so there is no remaining supply.
+#[derive(Serialize)]
+pub struct Dashboard {
+    pub total_minted: u64,
+    pub total_burned: u64,
+    pub current_supply: u64,
+    pub holder_count: u64,
+}
+
+/// Tokens minted and burned since a block height, returned by `supply_delta`.
+#[derive(Serialize)]
+pub struct SupplyDelta {
+    pub since_block_height: u64,
+    pub minted: u64,
+    pub burned: u64,
+}
+
+/// Per-call caps of the batch methods, configurable by the owner up to the hard bounds.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy)]
+pub struct BatchLimits {
+    pub id_gap_range: u64,
+    pub admin_transfer: u64,
+    pub soulbound: u64,
+    pub media: u64,
+    pub deferred_metadata: u64,
+    pub exist: u64,
+    pub snapshot: u64,
+    pub approvals: u64,
+    pub references: u64,
+}
+
+/// Everything a token detail page needs, returned by `nft_token_detail`.
+#[derive(Serialize)]
+pub struct TokenDetail {
+    pub token_id: TokenId,
+    pub owner_id: AccountId,
+    pub metadata: Option<TokenMetadata>,
+    pub approved_account_ids: Option<HashMap<AccountId, u64>>,
+    pub soulbound: bool,
+    pub unlock_ns: Option<u64>,
+    pub locked: bool,
+    pub content_rating: Option<String>,
+}
+
+/// How `add_whitelist_account` treats an account that is already whitelisted.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum Policy {
+    Ignore,
+    Error,
+}
+
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct Contract {
@@ -41,10 +163,201 @@ pub struct Contract {
     metadata: LazyOption<NFTContractMetadata>,
     mint_approval_status: Status,
     whitelist_accounts: Vec<AccountId>,
+    max_attached_deposit: Option<U128>,
+    whitelist_window: Option<(u64, u64)>,
+    require_reference_hash: bool,
+    approval_rate_limit: Option<(u64, u32)>,
+    approvals_this_window: LookupMap<AccountId, (u64, u32)>,
+    token_unlocks: UnorderedMap<TokenId, u64>,
+    /// Vested token ids by the account holding them while locked.
+    locked_by_owner: LookupMap<AccountId, UnorderedSet<TokenId>>,
+    /// Number of vested tokens by unlock timestamp.
+    unlock_counts: TreeMap<u64, u64>,
+    soulbound_tokens: LookupSet<TokenId>,
+    soulbound_frozen: bool,
+    collection_description: Option<String>,
+    paused_until_ns: Option<u64>,
+    token_minters: LookupMap<TokenId, AccountId>,
+    event_standard: String,
+    status_change_cooldown_ns: u64,
+    last_status_change_ns: Option<u64>,
+    total_minted: u64,
+    total_burned: u64,
+    /// Number of accounts holding at least one token.
+    holder_count: u64,
+    mint_deadline_ns: Option<u64>,
+    duplicate_whitelist_policy: Policy,
+    supply_history: Vec<(u64, u64, u64)>,
+    renderers: LookupMap<TokenId, AccountId>,
+    owner_minting_paused: bool,
+    trait_counts: LookupMap<String, u64>,
+    pending_recipients: LookupMap<TokenId, AccountId>,
+    /// Escrowed token ids by intended recipient, the reverse of `pending_recipients`.
+    pending_by_recipient: LookupMap<AccountId, UnorderedSet<TokenId>>,
+    compliance_accounts: Vec<AccountId>,
+    batch_limits: BatchLimits,
+    last_mint_gas: u64,
+    fallback_metadata: Option<TokenMetadata>,
+    minted_by: LookupMap<AccountId, Vector<TokenId>>,
+    oracle: Option<AccountId>,
+    mint_order: Vector<TokenId>,
+    deferred_tokens: LookupSet<TokenId>,
+    ownership_renounced: bool,
+    /// Account notified through `on_nft_transferred` after every transfer.
+    transfer_hook: Option<AccountId>,
+    /// `TokenMetadata` fields that can no longer be edited after mint.
+    frozen_fields: UnorderedSet<String>,
+    /// Nanoseconds after a burn before its id may be minted again; `None` never allows reuse.
+    allow_reuse_after_burn_ns: Option<u64>,
+    burned_at: LookupMap<TokenId, u64>,
+    /// Collection-wide content rating (e.g. `"safe"` or `"mature"`) for marketplace filtering.
+    content_rating: Option<String>,
+    token_content_ratings: LookupMap<TokenId, String>,
+    /// Holder balances by account, one map per `snapshot_holders` snapshot.
+    snapshots: Vector<HolderSnapshot>,
 }
 
 const DATA_IMAGE_SVG_NEAR_ICON: &str = "data:image/svg+xml,%3Csvg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 288 288'%3E%3Cg id='l' data-name='l'%3E%3Cpath d='M187.58,79.81l-30.1,44.69a3.2,3.2,0,0,0,4.75,4.2L191.86,103a1.2,1.2,0,0,1,2,.91v80.46a1.2,1.2,0,0,1-2.12.77L102.18,77.93A15.35,15.35,0,0,0,90.47,72.5H87.34A15.34,15.34,0,0,0,72,87.84V201.16A15.34,15.34,0,0,0,87.34,216.5h0a15.35,15.35,0,0,0,13.08-7.31l30.1-44.69a3.2,3.2,0,0,0-4.75-4.2L96.14,186a1.2,1.2,0,0,1-2-.91V104.61a1.2,1.2,0,0,1,2.12-.77l89.55,107.23a15.35,15.35,0,0,0,11.71,5.43h3.13A15.34,15.34,0,0,0,216,201.16V87.84A15.34,15.34,0,0,0,200.66,72.5h0A15.35,15.35,0,0,0,187.58,79.81Z'/%3E%3C/g%3E%3C/svg%3E";
 
+/// Hard upper bound for the number of ids `find_id_gaps` scans in a single call.
+const MAX_ID_GAP_RANGE: u64 = 1000;
+
+/// Hard upper bound for the number of tokens `admin_transfer_all` moves in a single call.
+const MAX_ADMIN_TRANSFER_BATCH: u64 = 50;
+
+/// Hard upper bound for the number of tokens `set_soulbound_batch` updates in a single call.
+const MAX_SOULBOUND_BATCH: u64 = 100;
+
+/// Hard upper bound for the number of tokens `set_media_batch` updates in a single call.
+const MAX_MEDIA_BATCH: u64 = 50;
+
+/// Hard upper bound for the number of tokens `assign_deferred_metadata` fills in a single call.
+const MAX_DEFERRED_METADATA_BATCH: u64 = 50;
+
+/// Hard upper bound for the number of ids `nft_exist_batch` checks in a single call.
+const MAX_EXIST_BATCH: u64 = 500;
+
+/// Hard upper bound for the number of tokens `snapshot_holders` reads in a single call.
+const MAX_SNAPSHOT_BATCH: u64 = 100;
+
+/// Hard upper bound for the number of tokens `all_approvals` lists approvals for in a single call.
+const MAX_APPROVALS_BATCH: u64 = 100;
+
+/// Hard upper bound for the number of tokens `export_references` exports in a single call.
+const MAX_REFERENCES_BATCH: u64 = 100;
+
+const GAS_FOR_TRANSFER_HOOK: Gas = Gas(5_000_000_000_000);
+// the resolver notifies the transfer hook when it returns a token
+const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas(5_000_000_000_000 + GAS_FOR_TRANSFER_HOOK.0);
+// the hook call is a third receipt on top of the receiver and the resolver
+const GAS_FOR_NFT_TRANSFER_CALL: Gas =
+    Gas(30_000_000_000_000 + GAS_FOR_RESOLVE_TRANSFER.0 + GAS_FOR_TRANSFER_HOOK.0);
+const GAS_FOR_RENDER: Gas = Gas(10_000_000_000_000);
+
+#[ext_contract(ext_nft_receiver)]
+pub trait NftReceiver {
+    fn nft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        previous_owner_id: AccountId,
+        token_id: TokenId,
+        msg: String,
+    ) -> PromiseOrValue<bool>;
+}
+
+/// Interface of a contract that renders a token's metadata dynamically.
+#[ext_contract(ext_renderer)]
+pub trait TokenRenderer {
+    fn render(&self, token_id: TokenId) -> Option<TokenMetadata>;
+}
+
+/// Interface of a contract that is notified of every transfer in the collection.
+#[ext_contract(ext_transfer_hook)]
+pub trait TransferHook {
+    fn on_nft_transferred(&mut self, token_id: TokenId, old_owner: AccountId, new_owner: AccountId);
+    fn on_nft_transferred_batch(
+        &mut self,
+        token_ids: Vec<TokenId>,
+        old_owner: AccountId,
+        new_owner: AccountId,
+    );
+}
+
+#[ext_contract(ext_self)]
+pub trait SelfResolver {
+    fn nft_resolve_transfer(
+        &mut self,
+        previous_owner_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approved_account_ids: Option<HashMap<AccountId, u64>>,
+    ) -> bool;
+}
+
+/// Standard name used for the contract's own (non NEP-171) events until the owner changes it.
+const DEFAULT_EVENT_STANDARD: &str = "qstn_admin";
+const ADMIN_EVENT_VERSION: &str = "1.0.0";
+
+/// Number of `(block_height, total_minted, total_burned)` entries kept for `supply_delta`.
+const SUPPLY_HISTORY_SIZE: usize = 64;
+
+/// Bytes charged for every storage record on top of its key and value.
+const STORAGE_BYTES_PER_RECORD: u64 = 40;
+
+/// Scale applied to each inverse trait frequency in `compute_rarity_score`.
+const RARITY_SCALE: u64 = 1000;
+
+/// `TokenMetadata` fields that `freeze_field` accepts.
+const TOKEN_METADATA_FIELDS: [&str; 12] = [
+    "title",
+    "description",
+    "media",
+    "media_hash",
+    "copies",
+    "issued_at",
+    "expires_at",
+    "starts_at",
+    "updated_at",
+    "extra",
+    "reference",
+    "reference_hash",
+];
+
+/// Trait keys (`"<trait_type>:<value>"`) parsed from the `attributes` array in `extra`.
+fn token_traits(metadata: &TokenMetadata) -> Vec<String> {
+    let extra: Value = match metadata
+        .extra
+        .as_deref()
+        .and_then(|extra| near_sdk::serde_json::from_str(extra).ok())
+    {
+        Some(extra) => extra,
+        None => return vec![],
+    };
+    extra["attributes"]
+        .as_array()
+        .map(|attributes| {
+            attributes
+                .iter()
+                .map(|attribute| format!("{}:{}", attribute["trait_type"], attribute["value"]))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Fills the display fields missing from `metadata` with those of `fallback`.
+fn apply_fallback_metadata(metadata: &mut TokenMetadata, fallback: &TokenMetadata) {
+    if metadata.title.is_none() {
+        metadata.title = fallback.title.clone();
+    }
+    if metadata.description.is_none() {
+        metadata.description = fallback.description.clone();
+    }
+    if metadata.media.is_none() {
+        metadata.media = fallback.media.clone();
+        metadata.media_hash = fallback.media_hash.clone();
+    }
+}
+
 #[derive(BorshSerialize, BorshStorageKey)]
 enum StorageKey {
     NonFungibleToken,
@@ -52,6 +365,27 @@ enum StorageKey {
     TokenMetadata,
     Enumeration,
     Approval,
+    ApprovalsThisWindow,
+    TokenUnlocks,
+    SoulboundTokens,
+    TokenMinters,
+    Renderers,
+    TraitCounts,
+    PendingRecipients,
+    MintedBy,
+    MintedByAccount { account_hash: Vec<u8> },
+    MintOrder,
+    DeferredTokens,
+    FrozenFields,
+    PendingByRecipient,
+    PendingByRecipientAccount { account_hash: Vec<u8> },
+    LockedByOwner,
+    LockedByOwnerAccount { account_hash: Vec<u8> },
+    BurnedAt,
+    TokenContentRatings,
+    UnlockCounts,
+    Snapshots,
+    SnapshotHolders { snapshot_id: u64 },
 }
 
 #[near_bindgen]
@@ -78,7 +412,7 @@ impl Contract {
     pub fn new(owner_id: AccountId, metadata: NFTContractMetadata) -> Self {
         assert!(!env::state_exists(), "Already initialized");
         metadata.assert_valid();
-        Self {
+        Self::from_base(BaseState {
             tokens: NonFungibleToken::new(
                 StorageKey::NonFungibleToken,
                 owner_id,
@@ -89,7 +423,30 @@ impl Contract {
             metadata: LazyOption::new(StorageKey::Metadata, Some(&metadata)),
             mint_approval_status: Status::None,
             whitelist_accounts: vec![],
-        }
+        })
+    }
+
+    /// Upgrades state written by the original contract to the current layout. Tokens, metadata,
+    /// mint status and whitelist are kept, and every later setting starts at its default. The
+    /// supply and holder counters are rebuilt from the existing tokens in this call, so it must
+    /// run before the collection outgrows one call's gas. Minters, mint order and trait counts
+    /// were never recorded, so tokens minted before the upgrade are missing from those indexes.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let base: BaseState =
+            env::state_read().unwrap_or_else(|| env::panic_str("No state to migrate"));
+        let mut contract = Self::from_base(base);
+        // The original contract had no burn, so every stored token was minted.
+        contract.total_minted = contract.tokens.owner_by_id.len();
+        contract.holder_count = contract
+            .tokens
+            .owner_by_id
+            .iter()
+            .map(|(_, owner_id)| owner_id)
+            .collect::<HashSet<_>>()
+            .len() as u64;
+        contract
     }
 
     /// Mint a new token with ID=`token_id` belonging to `receiver_id`.
@@ -108,99 +465,3417 @@ impl Contract {
         token_metadata: TokenMetadata,
     ) -> Token {
         // self.tokens.mint(token_id, receiver_id, Some(token_metadata))
-        // owner can mint nft irrespective of mint_approval_status
-        if env::current_account_id() == env::predecessor_account_id() {
-            return self
-                .tokens
-                .internal_mint(token_id, receiver_id, Some(token_metadata));
-        }
-        match self.mint_approval_status {
-            Status::All => {
-                return self
-                    .tokens
-                    .internal_mint(token_id, receiver_id, Some(token_metadata));
-            }
-            Status::Whitelist => {
-                assert!(
-                    self.whitelist_accounts
-                        .contains(&env::predecessor_account_id()),
-                    "Only Whitelist Accounts can mint NFT"
-                );
-                return self
-                    .tokens
-                    .internal_mint(token_id, receiver_id, Some(token_metadata));
-            }
-            Status::None => {
-                panic!("Minting is not allowed for Now")
-            }
-        }
+        self.assert_mint_allowed(&token_metadata);
+        self.internal_mint(
+            token_id,
+            receiver_id,
+            token_metadata,
+            Some(env::predecessor_account_id()),
+        )
     }
 
     #[payable]
     pub fn add_whitelist_account(&mut self, whitelist_account: AccountId) -> bool {
         //Checks only contract owner can add whitelist account
-        assert!(
-            env::current_account_id() == env::predecessor_account_id(),
-            "Only Contract owner can add whitelist account"
-        );
+        self.assert_owner("Only Contract owner can add whitelist account");
+        if self.whitelist_accounts.contains(&whitelist_account) {
+            assert!(
+                self.duplicate_whitelist_policy == Policy::Ignore,
+                "Account is already whitelisted"
+            );
+            return true;
+        }
         self.whitelist_accounts.push(whitelist_account);
-        return true;
+        true
     }
 
     #[payable]
     pub fn remove_whitelist_account(&mut self, whitelist_account: AccountId) -> bool {
         //Checks only contract owner can add whitelist account
-        assert!(
-            env::current_account_id() == env::predecessor_account_id(),
-            "Only Contract owner can add whitelist account"
-        );
+        self.assert_owner("Only Contract owner can add whitelist account");
         if let Some(index) = self.whitelist_accounts.iter().position(|x| x == &whitelist_account) {
             self.whitelist_accounts.remove(index);
         }
-        return true;
+        true
     }
 
     pub fn get_whitelist_accounts(self) -> Vec<AccountId> {
-        return self.whitelist_accounts;
+        self.whitelist_accounts
     }
 
+    /// Replaces the whitelist, sets the whitelist minting window (nanosecond timestamps,
+    /// `[start, end)`) and switches the mint approval status to `Whitelist` in one call.
     #[payable]
-    pub fn change_nft_approval_status(&mut self, approval_status: String) {
-        //Checks only contract owner can change NFT Mint approval
+    pub fn open_whitelist_phase(&mut self, accounts: Vec<AccountId>, window: Option<(u64, u64)>) {
+        //Checks only contract owner can open the whitelist phase
+        self.assert_owner("Only Contract owner can open whitelist phase");
+        if let Some((start, end)) = window {
+            assert!(start < end, "Window start must be before window end");
+        }
+        self.whitelist_accounts = accounts;
+        self.whitelist_window = window;
+        self.internal_set_approval_status("whitelist");
+    }
+
+    pub fn get_whitelist_window(&self) -> Option<(u64, u64)> {
+        self.whitelist_window
+    }
+
+    /// When enabled, mints are rejected if `reference` is set without `reference_hash`.
+    #[payable]
+    pub fn set_require_reference_hash(&mut self, require_reference_hash: bool) {
+        //Checks only contract owner can change the reference hash requirement
+        self.assert_owner("Only Contract owner can change reference hash requirement");
+        self.require_reference_hash = require_reference_hash;
+    }
+
+    pub fn get_require_reference_hash(&self) -> bool {
+        self.require_reference_hash
+    }
+
+    /// Returns the numeric ids in `from..=to` that have no owner, i.e. were never minted
+    /// (or no longer exist). At most `batch_limits.id_gap_range` ids are scanned per call.
+    pub fn find_id_gaps(&self, from: u64, to: u64) -> Vec<u64> {
+        assert!(from <= to, "from must not be greater than to");
         assert!(
-            env::current_account_id() == env::predecessor_account_id(),
-            "Only Contract owner can change NFT Mint approval"
+            to - from < self.batch_limits.id_gap_range,
+            "Range exceeds maximum of {} ids",
+            self.batch_limits.id_gap_range
         );
-        match approval_status.as_str() {
-            "all" => {
-                log!("NFT approval status is set to ALL");
-                self.mint_approval_status = Status::All
+        (from..=to)
+            .filter(|id| !self.tokens.owner_by_id.contains_key(&id.to_string()))
+            .collect()
+    }
+
+    /// Whether each of `token_ids` currently exists, in input order. At most
+    /// `batch_limits.exist` ids are checked per call.
+    pub fn nft_exist_batch(&self, token_ids: Vec<TokenId>) -> Vec<bool> {
+        assert!(
+            token_ids.len() as u64 <= self.batch_limits.exist,
+            "Batch exceeds maximum of {} tokens",
+            self.batch_limits.exist
+        );
+        token_ids
+            .iter()
+            .map(|token_id| self.tokens.owner_by_id.contains_key(token_id))
+            .collect()
+    }
+
+    /// Moves up to `limit` (capped at `batch_limits.admin_transfer`) tokens owned by `from` to
+    /// `to`, clearing their approvals and refunding their storage to `from`. Escrowed tokens
+    /// awaiting review are left in place. Returns the number of tokens moved; call again until it
+    /// returns 0 to migrate the whole account.
+    #[payable]
+    pub fn admin_transfer_all(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        limit: Option<u64>,
+    ) -> u64 {
+        //Checks only contract owner can migrate tokens between accounts
+        self.assert_owner("Only Contract owner can transfer all tokens");
+        assert!(from != to, "Current and next owner must differ");
+        let limit = limit
+            .unwrap_or(self.batch_limits.admin_transfer)
+            .min(self.batch_limits.admin_transfer);
+        let token_ids: Vec<TokenId> = match self
+            .tokens
+            .tokens_per_owner
+            .as_ref()
+            .and_then(|by_owner| by_owner.get(&from))
+        {
+            Some(owner_tokens) => owner_tokens
+                .iter()
+                .filter(|token_id| !self.pending_recipients.contains_key(token_id))
+                .take(limit as usize)
+                .collect(),
+            None => return 0,
+        };
+        let to_was_holder = self.is_holder(&to);
+        for token_id in &token_ids {
+            if let Some(approvals_by_id) = &mut self.tokens.approvals_by_id {
+                if let Some(approvals) = approvals_by_id.remove(token_id) {
+                    refund_approved_account_ids(from.clone(), &approvals);
+                }
             }
-            "whitelist" => {
-                log!("NFT approval status is set to Whitelist ");
-                self.mint_approval_status = Status::Whitelist
+            self.tokens
+                .internal_transfer_unguarded(token_id, &from, &to);
+            self.prune_expired_lock(token_id);
+            if self.token_unlocks.get(token_id).is_some() {
+                self.unindex_lock(&from, token_id);
+                self.index_lock(&to, token_id);
             }
-            "none" => {
-                log!("NFT approval status is set to Nne ");
-                self.mint_approval_status = Status::None
+        }
+        self.update_holder_count(&from, true);
+        self.update_holder_count(&to, to_was_holder);
+        let moved = token_ids.len() as u64;
+        if !token_ids.is_empty() {
+            let event_token_ids: Vec<&str> = token_ids.iter().map(|id| id.as_str()).collect();
+            NftTransfer {
+                old_owner_id: &from,
+                new_owner_id: &to,
+                token_ids: &event_token_ids,
+                authorized_id: None,
+                memo: Some("admin_transfer_all"),
             }
-            _ => panic!("Invalid approval status: {}", approval_status),
-        };
+            .emit();
+            self.notify_transfer_hook_batch(token_ids, from, to);
+        }
+        moved
+    }
+
+    /// Limits every non-owner account to `max_approvals` calls of `nft_approve` per window of
+    /// `window_ns` nanoseconds. `None` removes the limit.
+    #[payable]
+    pub fn set_approval_rate_limit(&mut self, approval_rate_limit: Option<(u64, u32)>) {
+        //Checks only contract owner can change the approval rate limit
+        self.assert_owner("Only Contract owner can change approval rate limit");
+        if let Some((window_ns, _)) = approval_rate_limit {
+            assert!(window_ns > 0, "Approval window must be positive");
+        }
+        self.approval_rate_limit = approval_rate_limit;
+    }
+
+    pub fn get_approval_rate_limit(&self) -> Option<(u64, u32)> {
+        self.approval_rate_limit
+    }
+
+    /// Number of approvals `account_id` has made in the current rate limit window.
+    pub fn approvals_this_window(&self, account_id: AccountId) -> u32 {
+        match (
+            self.approval_rate_limit,
+            self.approvals_this_window.get(&account_id),
+        ) {
+            (Some((window_ns, _)), Some((window, count)))
+                if window == env::block_timestamp() / window_ns =>
+            {
+                count
+            }
+            _ => 0,
+        }
+    }
+
+    /// Cost in yoctoNEAR per byte of storage, as used for storage deposits and refunds.
+    pub fn storage_byte_cost(&self) -> U128 {
+        U128(env::storage_byte_cost())
+    }
+
+    #[payable]
+    pub fn change_nft_approval_status(&mut self, approval_status: String) {
+        //Checks only contract owner can change NFT Mint approval
+        self.assert_owner("Only Contract owner can change NFT Mint approval");
+        self.internal_set_approval_status(&approval_status);
     }
 
     pub fn get_nft_approval_status(self) -> Status {
-        return self.mint_approval_status;
+        self.mint_approval_status
     }
 
-}
+    /// Sets the maximum deposit a single mint may attach. `None` removes the cap.
+    #[payable]
+    pub fn set_max_attached_deposit(&mut self, max_attached_deposit: Option<U128>) {
+        //Checks only contract owner can change the deposit cap
+        self.assert_owner("Only Contract owner can change max attached deposit");
+        self.max_attached_deposit = max_attached_deposit;
+    }
 
-near_contract_standards::impl_non_fungible_token_core!(Contract, tokens);
-near_contract_standards::impl_non_fungible_token_approval!(Contract, tokens);
-near_contract_standards::impl_non_fungible_token_enumeration!(Contract, tokens);
+    pub fn get_max_attached_deposit(&self) -> Option<U128> {
+        self.max_attached_deposit
+    }
 
-#[near_bindgen]
-impl NonFungibleTokenMetadataProvider for Contract {
-    fn nft_metadata(&self) -> NFTContractMetadata {
-        self.metadata.get().unwrap()
+    /// Mints like `nft_mint` and locks the token against transfers until `unlock_ns`
+    /// (block timestamp in nanoseconds).
+    #[payable]
+    pub fn nft_mint_vested(
+        &mut self,
+        token_id: TokenId,
+        receiver_id: AccountId,
+        token_metadata: TokenMetadata,
+        unlock_ns: u64,
+    ) -> Token {
+        //Checks only contract owner can mint vested NFT
+        self.assert_owner("Only Contract owner can mint vested NFT");
+        let token = self.nft_mint(token_id, receiver_id, token_metadata);
+        self.internal_lock(&token.owner_id, &token.token_id, unlock_ns);
+        token
+    }
+
+    pub fn token_unlock(&self, token_id: TokenId) -> Option<u64> {
+        self.token_unlocks.get(&token_id)
+    }
+
+    /// Marks (or unmarks) `token_ids` as soulbound, blocking their transfer.
+    /// Rejected once `freeze_soulbound` has been called.
+    #[payable]
+    pub fn set_soulbound_batch(&mut self, token_ids: Vec<TokenId>, soulbound: bool) {
+        //Checks only contract owner can change soulbound tokens
+        self.assert_owner("Only Contract owner can change soulbound tokens");
+        assert!(!self.soulbound_frozen, "Soulbound tokens are frozen");
+        assert!(
+            token_ids.len() as u64 <= self.batch_limits.soulbound,
+            "Batch exceeds maximum of {} tokens",
+            self.batch_limits.soulbound
+        );
+        for token_id in &token_ids {
+            assert!(
+                self.tokens.owner_by_id.contains_key(token_id),
+                "Token not found: {}",
+                token_id
+            );
+            if soulbound {
+                self.soulbound_tokens.insert(token_id);
+            } else {
+                self.soulbound_tokens.remove(token_id);
+            }
+        }
+    }
+
+    /// Permanently locks the set of soulbound tokens. This cannot be undone.
+    #[payable]
+    pub fn freeze_soulbound(&mut self) {
+        //Checks only contract owner can freeze soulbound tokens
+        self.assert_owner("Only Contract owner can freeze soulbound tokens");
+        self.soulbound_frozen = true;
+        self.emit_admin_event("soulbound_frozen", json!({}));
+    }
+
+    pub fn is_soulbound(&self, token_id: TokenId) -> bool {
+        self.soulbound_tokens.contains(&token_id)
+    }
+
+    pub fn is_soulbound_frozen(&self) -> bool {
+        self.soulbound_frozen
+    }
+
+    /// Mints `token_id` to the caller, subject to the same rules as `nft_mint`, and immediately
+    /// transfers it to `receiver_id` with `nft_transfer_call` semantics. Returns the result of
+    /// the transfer, i.e. `false` if the receiver rejected the token and it was returned.
+    #[payable]
+    pub fn nft_mint_and_transfer_call(
+        &mut self,
+        token_id: TokenId,
+        receiver_id: AccountId,
+        token_metadata: TokenMetadata,
+        msg: String,
+    ) -> PromiseOrValue<bool> {
+        require!(
+            env::prepaid_gas() > GAS_FOR_NFT_TRANSFER_CALL,
+            "More gas is required"
+        );
+        let sender_id = env::predecessor_account_id();
+        self.nft_mint(token_id.clone(), sender_id.clone(), token_metadata);
+        let receiver_was_holder = self.is_holder(&receiver_id);
+        let (old_owner, old_approvals) =
+            self.tokens
+                .internal_transfer(&sender_id, &receiver_id, &token_id, None, None);
+        self.update_holder_count(&old_owner, true);
+        self.update_holder_count(&receiver_id, receiver_was_holder);
+        self.notify_transfer_hook(token_id.clone(), old_owner.clone(), receiver_id.clone());
+        ext_nft_receiver::ext(receiver_id.clone())
+            .with_static_gas(env::prepaid_gas() - GAS_FOR_NFT_TRANSFER_CALL)
+            .nft_on_transfer(sender_id, old_owner.clone(), token_id.clone(), msg)
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .nft_resolve_transfer(old_owner, receiver_id, token_id, old_approvals),
+            )
+            .into()
+    }
+
+    /// Sets a collection description kept alongside `NFTContractMetadata`, which has no
+    /// description field of its own.
+    #[payable]
+    pub fn set_collection_description(&mut self, text: String) {
+        //Checks only contract owner can change the collection description
+        self.assert_owner("Only Contract owner can change collection description");
+        self.collection_description = Some(text);
+    }
+
+    pub fn get_collection_description(&self) -> Option<String> {
+        self.collection_description.clone()
+    }
+
+    pub fn nft_token_detail(&self, token_id: TokenId) -> Option<TokenDetail> {
+        let token = self.nft_token(token_id)?;
+        let soulbound = self.soulbound_tokens.contains(&token.token_id);
+        let unlock_ns = self.token_unlocks.get(&token.token_id);
+        let locked = unlock_ns.is_some_and(|unlock_ns| env::block_timestamp() < unlock_ns);
+        Some(TokenDetail {
+            content_rating: self.get_token_content_rating(token.token_id.clone()),
+            token_id: token.token_id,
+            owner_id: token.owner_id,
+            metadata: token.metadata,
+            approved_account_ids: token.approved_account_ids,
+            soulbound,
+            unlock_ns,
+            locked,
+        })
+    }
+
+    /// Applies every `Some` field of `config` in a single transaction. All fields are
+    /// validated before any of them is applied.
+    #[payable]
+    pub fn bulk_configure(&mut self, config: PartialConfig) {
+        //Checks only contract owner can change the configuration
+        self.assert_owner("Only Contract owner can change configuration");
+        if let Some((start, end)) = config.whitelist_window {
+            assert!(start < end, "Window start must be before window end");
+        }
+        if let Some((window_ns, _)) = config.approval_rate_limit {
+            assert!(window_ns > 0, "Approval window must be positive");
+        }
+        if let Some(approval_status) = config.approval_status {
+            self.internal_set_approval_status(&approval_status);
+        }
+        if let Some(whitelist_window) = config.whitelist_window {
+            self.whitelist_window = Some(whitelist_window);
+        }
+        if let Some(max_attached_deposit) = config.max_attached_deposit {
+            self.max_attached_deposit = Some(max_attached_deposit);
+        }
+        if let Some(require_reference_hash) = config.require_reference_hash {
+            self.require_reference_hash = require_reference_hash;
+        }
+        if let Some(approval_rate_limit) = config.approval_rate_limit {
+            self.approval_rate_limit = Some(approval_rate_limit);
+        }
+        if let Some(content_rating) = config.content_rating {
+            self.content_rating = Some(content_rating);
+        }
+    }
+
+    /// Lists vested tokens that are still locked, with the nanoseconds remaining until unlock.
+    /// Pagination runs over all vested tokens, so a page may hold fewer than `limit` entries.
+    pub fn nft_vesting_tokens(
+        &self,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<(TokenId, u64)> {
+        let now = env::block_timestamp();
+        self.token_unlocks
+            .iter()
+            .skip(from_index.map_or(0, |index| index.0 as usize))
+            .take(limit.map_or(usize::MAX, |limit| limit as usize))
+            .filter(|(_, unlock_ns)| *unlock_ns > now)
+            .map(|(token_id, unlock_ns)| (token_id, unlock_ns - now))
+            .collect()
+    }
+
+    /// Number of vested tokens that are still locked.
+    pub fn locked_token_count(&self) -> u64 {
+        self.unlock_counts
+            .iter_from(env::block_timestamp())
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// Vested tokens held by `account_id` that are still locked.
+    pub fn locked_tokens_for(&self, account_id: AccountId) -> Vec<TokenId> {
+        let now = env::block_timestamp();
+        match self.locked_by_owner.get(&account_id) {
+            Some(locked) => locked
+                .iter()
+                .filter(|token_id| {
+                    self.token_unlocks
+                        .get(token_id)
+                        .is_some_and(|unlock_ns| unlock_ns > now)
+                })
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Reports whether `account_id` could mint a token with `token_metadata` right now, applying
+    /// the same checks as `nft_mint`, and the deposit to attach. The deposit is an upper bound on
+    /// the storage cost for token ids of up to 64 characters; any excess is refunded by the mint.
+    pub fn mint_requirements(
+        &self,
+        account_id: AccountId,
+        token_metadata: TokenMetadata,
+    ) -> MintRequirements {
+        let storage_bytes = self.mint_storage_bytes(&account_id, &token_metadata);
+        let required_deposit = env::storage_byte_cost() * storage_bytes as u128;
+        let reason = self
+            .token_metadata_error(&token_metadata)
+            .or_else(|| self.mint_denial_reason(&account_id))
+            .or_else(|| match &self.max_attached_deposit {
+                Some(max_attached_deposit) if required_deposit > max_attached_deposit.0 => {
+                    Some("Deposit exceeds maximum".to_string())
+                }
+                _ => None,
+            });
+        MintRequirements {
+            required_deposit: U128(required_deposit),
+            allowed: reason.is_none(),
+            reason,
+        }
+    }
+
+    /// Pauses minting and transfers until the block timestamp reaches `paused_until_ns`.
+    /// The pause lifts by itself; `None` lifts it immediately.
+    #[payable]
+    pub fn set_paused_until(&mut self, paused_until_ns: Option<u64>) {
+        //Checks only contract owner can pause the contract
+        self.assert_owner("Only Contract owner can pause the contract");
+        self.paused_until_ns = paused_until_ns;
+    }
+
+    pub fn get_paused_until(&self) -> Option<u64> {
+        self.paused_until_ns
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_until_ns
+            .is_some_and(|paused_until_ns| env::block_timestamp() < paused_until_ns)
+    }
+
+    /// Account that minted `token_id`, which stays the same when the token is transferred. It is
+    /// kept after a burn, until the id is minted again.
+    pub fn nft_minter(&self, token_id: TokenId) -> Option<AccountId> {
+        self.token_minters.get(&token_id)
+    }
+
+    /// Sets the `standard` field of the contract's admin events. NEP-171 events are unaffected.
+    #[payable]
+    pub fn set_event_standard(&mut self, event_standard: String) {
+        //Checks only contract owner can change the event standard
+        self.assert_owner("Only Contract owner can change event standard");
+        assert!(
+            !event_standard.is_empty(),
+            "Event standard must not be empty"
+        );
+        self.event_standard = event_standard;
+    }
+
+    pub fn get_event_standard(&self) -> String {
+        self.event_standard.clone()
+    }
+
+    /// Lists `(token_id, approved_account_id, approval_id)` for every approval on the tokens in
+    /// the page. Pagination runs over tokens, in the same order as `nft_tokens`, and at most
+    /// `batch_limits.approvals` tokens are read per call.
+    pub fn all_approvals(
+        &self,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<(TokenId, AccountId, u64)> {
+        let approvals_by_id = match &self.tokens.approvals_by_id {
+            Some(approvals_by_id) => approvals_by_id,
+            None => return vec![],
+        };
+        let limit = limit
+            .unwrap_or(self.batch_limits.approvals)
+            .min(self.batch_limits.approvals);
+        self.tokens
+            .owner_by_id
+            .iter()
+            .skip(from_index.map_or(0, |index| index.0 as usize))
+            .take(limit as usize)
+            .flat_map(|(token_id, _)| {
+                approvals_by_id
+                    .get(&token_id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(move |(account_id, approval_id)| {
+                        (token_id.clone(), account_id, approval_id)
+                    })
+            })
+            .collect()
+    }
+
+    /// Minimum time in nanoseconds between two changes of the mint approval status.
+    #[payable]
+    pub fn set_status_change_cooldown(&mut self, status_change_cooldown_ns: u64) {
+        //Checks only contract owner can change the status change cooldown
+        self.assert_owner("Only Contract owner can change status change cooldown");
+        self.status_change_cooldown_ns = status_change_cooldown_ns;
+    }
+
+    pub fn get_status_change_cooldown(&self) -> u64 {
+        self.status_change_cooldown_ns
+    }
+
+    pub fn get_last_status_change(&self) -> Option<u64> {
+        self.last_status_change_ns
+    }
+
+    pub fn supply_snapshot(&self) -> SupplySnapshot {
+        SupplySnapshot {
+            total_minted: self.total_minted,
+            total_burned: self.total_burned,
+            current_supply: self.tokens.owner_by_id.len(),
+            block_height: env::block_height(),
+        }
+    }
+
+    pub fn get_dashboard(&self) -> Dashboard {
+        Dashboard {
+            total_minted: self.total_minted,
+            total_burned: self.total_burned,
+            current_supply: self.tokens.owner_by_id.len(),
+            holder_count: self.holder_count,
+        }
+    }
+
+    /// Sets a hard deadline (nanosecond timestamp) after which no one, including the owner,
+    /// can mint.
+    #[payable]
+    pub fn set_mint_deadline(&mut self, mint_deadline_ns: Option<u64>) {
+        //Checks only contract owner can change the mint deadline
+        self.assert_owner("Only Contract owner can change mint deadline");
+        self.mint_deadline_ns = mint_deadline_ns;
+    }
+
+    pub fn get_mint_deadline(&self) -> Option<u64> {
+        self.mint_deadline_ns
+    }
+
+    #[payable]
+    pub fn set_duplicate_whitelist_policy(&mut self, duplicate_whitelist_policy: Policy) {
+        //Checks only contract owner can change the duplicate whitelist policy
+        self.assert_owner("Only Contract owner can change duplicate whitelist policy");
+        self.duplicate_whitelist_policy = duplicate_whitelist_policy;
+    }
+
+    pub fn get_duplicate_whitelist_policy(&self) -> Policy {
+        self.duplicate_whitelist_policy
+    }
+
+    /// Tokens minted and burned after `since_block_height`. Only the last
+    /// `SUPPLY_HISTORY_SIZE` blocks with supply changes are retained, so older heights panic.
+    pub fn supply_delta(&self, since_block_height: u64) -> SupplyDelta {
+        let base = match self
+            .supply_history
+            .iter()
+            .rev()
+            .find(|(block_height, _, _)| *block_height <= since_block_height)
+        {
+            Some((_, minted, burned)) => (*minted, *burned),
+            None if self.supply_history.len() < SUPPLY_HISTORY_SIZE => (0, 0),
+            None => env::panic_str("Supply history does not reach this block height"),
+        };
+        SupplyDelta {
+            since_block_height,
+            minted: self.total_minted - base.0,
+            burned: self.total_burned - base.1,
+        }
+    }
+
+    /// Points `token_id` at a renderer contract used by `nft_render`. `None` removes it.
+    #[payable]
+    pub fn set_renderer(&mut self, token_id: TokenId, renderer: Option<AccountId>) {
+        //Checks only contract owner can change token renderers
+        self.assert_owner("Only Contract owner can change token renderer");
+        assert!(
+            self.tokens.owner_by_id.contains_key(&token_id),
+            "Token not found"
+        );
+        match renderer {
+            Some(renderer) => self.renderers.insert(&token_id, &renderer),
+            None => self.renderers.remove(&token_id),
+        };
     }
-}
\ No newline at end of file
+
+    pub fn get_renderer(&self, token_id: TokenId) -> Option<AccountId> {
+        self.renderers.get(&token_id)
+    }
+
+    /// Returns the metadata produced by the token's renderer, or its stored metadata when no
+    /// renderer is set. This is a change method so that it can call the renderer.
+    pub fn nft_render(&mut self, token_id: TokenId) -> PromiseOrValue<Option<TokenMetadata>> {
+        match self.renderers.get(&token_id) {
+            Some(renderer) => ext_renderer::ext(renderer)
+                .with_static_gas(GAS_FOR_RENDER)
+                .render(token_id)
+                .into(),
+            None => PromiseOrValue::Value(
+                self.tokens
+                    .token_metadata_by_id
+                    .as_ref()
+                    .and_then(|by_id| by_id.get(&token_id)),
+            ),
+        }
+    }
+
+    /// Lists `(token_id, reference, reference_hash)` for a page of tokens, in the same order as
+    /// `nft_tokens`, so content addresses can be backed up off-chain. At most
+    /// `batch_limits.references` tokens are exported per call.
+    pub fn export_references(
+        &self,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<(TokenId, Option<String>, Option<Vec<u8>>)> {
+        let token_metadata_by_id = match &self.tokens.token_metadata_by_id {
+            Some(token_metadata_by_id) => token_metadata_by_id,
+            None => return vec![],
+        };
+        let limit = limit
+            .unwrap_or(self.batch_limits.references)
+            .min(self.batch_limits.references);
+        self.tokens
+            .owner_by_id
+            .iter()
+            .skip(from_index.map_or(0, |index| index.0 as usize))
+            .take(limit as usize)
+            .map(|(token_id, _)| {
+                let metadata = token_metadata_by_id.get(&token_id);
+                let reference = metadata.as_ref().and_then(|m| m.reference.clone());
+                let reference_hash = metadata.and_then(|m| m.reference_hash).map(|hash| hash.0);
+                (token_id, reference, reference_hash)
+            })
+            .collect()
+    }
+
+    /// Returns true if the sha256 of `content` equals the token's stored `reference_hash`.
+    /// Returns false when the token has no `reference_hash`.
+    pub fn verify_reference_hash(&self, token_id: TokenId, content: Vec<u8>) -> bool {
+        self.tokens
+            .token_metadata_by_id
+            .as_ref()
+            .and_then(|by_id| by_id.get(&token_id))
+            .and_then(|metadata| metadata.reference_hash)
+            .is_some_and(|reference_hash| reference_hash.0 == env::sha256(&content))
+    }
+
+    /// Makes the owner's own mints subject to the same status, whitelist and pause checks as
+    /// everyone else. This cannot be undone.
+    #[payable]
+    pub fn pause_owner_minting(&mut self) {
+        //Checks only contract owner can pause its own minting
+        self.assert_owner("Only Contract owner can pause owner minting");
+        self.owner_minting_paused = true;
+        self.emit_admin_event("owner_minting_paused", json!({}));
+    }
+
+    pub fn is_owner_minting_paused(&self) -> bool {
+        self.owner_minting_paused
+    }
+
+    /// Rarity of a token from its traits: the sum over its traits of
+    /// `current_supply * RARITY_SCALE / tokens_with_that_trait`, so rarer traits score higher.
+    /// Traits are read from `extra` as `{"attributes": [{"trait_type": .., "value": ..}]}`.
+    pub fn compute_rarity_score(&self, token_id: TokenId) -> Option<u32> {
+        let metadata = self
+            .tokens
+            .token_metadata_by_id
+            .as_ref()
+            .and_then(|by_id| by_id.get(&token_id))?;
+        let score: u64 = token_traits(&metadata)
+            .iter()
+            .filter_map(|token_trait| self.trait_counts.get(token_trait))
+            .map(|count| self.tokens.owner_by_id.len() * RARITY_SCALE / count)
+            .sum();
+        Some(score.min(u32::MAX as u64) as u32)
+    }
+
+    /// Mints `token_id` into escrow (owned by this contract) for `receiver_id`, subject to the
+    /// same rules as `nft_mint`. The token is delivered by `approve_pending` or burned by
+    /// `reject_pending`.
+    #[payable]
+    pub fn nft_mint_pending(
+        &mut self,
+        token_id: TokenId,
+        receiver_id: AccountId,
+        token_metadata: TokenMetadata,
+    ) -> Token {
+        self.assert_mint_allowed(&token_metadata);
+        let initial_storage_usage = env::storage_usage();
+        let token = self.internal_mint(token_id, env::current_account_id(), token_metadata, None);
+        self.pending_recipients
+            .insert(&token.token_id, &receiver_id);
+        let mut pending = self
+            .pending_by_recipient
+            .get(&receiver_id)
+            .unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::PendingByRecipientAccount {
+                    account_hash: env::sha256(receiver_id.as_bytes()),
+                })
+            });
+        pending.insert(&token.token_id);
+        self.pending_by_recipient.insert(&receiver_id, &pending);
+        refund_deposit(env::storage_usage() - initial_storage_usage);
+        token
+    }
+
+    /// Delivers an escrowed token to its intended recipient.
+    #[payable]
+    pub fn approve_pending(&mut self, token_id: TokenId) {
+        self.assert_compliance();
+        let receiver_id = self.internal_remove_pending(&token_id);
+        let contract_id = env::current_account_id();
+        let receiver_was_holder = self.is_holder(&receiver_id);
+        self.tokens
+            .internal_transfer_unguarded(&token_id, &contract_id, &receiver_id);
+        self.update_holder_count(&contract_id, true);
+        self.update_holder_count(&receiver_id, receiver_was_holder);
+        NftTransfer {
+            old_owner_id: &contract_id,
+            new_owner_id: &receiver_id,
+            token_ids: &[&token_id],
+            authorized_id: None,
+            memo: Some("approve_pending"),
+        }
+        .emit();
+        self.notify_transfer_hook(token_id, contract_id, receiver_id);
+    }
+
+    /// Burns an escrowed token and refunds its released storage to the account that minted it.
+    #[payable]
+    pub fn reject_pending(&mut self, token_id: TokenId) {
+        self.assert_compliance();
+        let initial_storage_usage = env::storage_usage();
+        self.internal_remove_pending(&token_id);
+        let minter = self.token_minters.get(&token_id);
+        self.internal_burn(&token_id);
+        let released = initial_storage_usage - env::storage_usage();
+        if let Some(minter) = minter {
+            Promise::new(minter).transfer(env::storage_byte_cost() * released as u128);
+        }
+    }
+
+    pub fn get_pending_recipient(&self, token_id: TokenId) -> Option<AccountId> {
+        self.pending_recipients.get(&token_id)
+    }
+
+    /// Escrowed tokens waiting for approval before delivery to `account_id`.
+    pub fn pending_tokens_for(&self, account_id: AccountId) -> Vec<TokenId> {
+        self.pending_by_recipient
+            .get(&account_id)
+            .map_or_else(Vec::new, |pending| pending.to_vec())
+    }
+
+    /// Replaces the accounts that, besides the owner, may approve or reject escrowed mints.
+    #[payable]
+    pub fn set_compliance_accounts(&mut self, accounts: Vec<AccountId>) {
+        //Checks only contract owner can change compliance accounts
+        self.assert_owner("Only Contract owner can change compliance accounts");
+        self.compliance_accounts = accounts;
+    }
+
+    pub fn get_compliance_accounts(&self) -> Vec<AccountId> {
+        self.compliance_accounts.clone()
+    }
+
+    pub fn get_compliance_config(&self) -> ComplianceConfig {
+        ComplianceConfig {
+            kyc_escrow: true,
+            receiver_allowlist: false,
+            require_registered: false,
+            compliance_accounts: self.compliance_accounts.clone(),
+        }
+    }
+
+    /// Sets the per-call caps of the batch methods. Each cap must be positive and within the
+    /// contract's hard bound.
+    #[payable]
+    pub fn set_batch_limits(&mut self, batch_limits: BatchLimits) {
+        //Checks only contract owner can change batch limits
+        self.assert_owner("Only Contract owner can change batch limits");
+        for (name, value, max) in [
+            ("id_gap_range", batch_limits.id_gap_range, MAX_ID_GAP_RANGE),
+            (
+                "admin_transfer",
+                batch_limits.admin_transfer,
+                MAX_ADMIN_TRANSFER_BATCH,
+            ),
+            ("soulbound", batch_limits.soulbound, MAX_SOULBOUND_BATCH),
+            ("media", batch_limits.media, MAX_MEDIA_BATCH),
+            (
+                "deferred_metadata",
+                batch_limits.deferred_metadata,
+                MAX_DEFERRED_METADATA_BATCH,
+            ),
+            ("exist", batch_limits.exist, MAX_EXIST_BATCH),
+            ("snapshot", batch_limits.snapshot, MAX_SNAPSHOT_BATCH),
+            ("approvals", batch_limits.approvals, MAX_APPROVALS_BATCH),
+            ("references", batch_limits.references, MAX_REFERENCES_BATCH),
+        ] {
+            assert!(
+                value > 0 && value <= max,
+                "{} must be between 1 and {}",
+                name,
+                max
+            );
+        }
+        self.batch_limits = batch_limits;
+    }
+
+    pub fn get_batch_limits(&self) -> BatchLimits {
+        self.batch_limits
+    }
+
+    /// Mints like `nft_mint`, filling a missing `media` or `reference` with
+    /// `<base_uri>/<folder>/<token_id>` (or `<folder>/<token_id>` when no `base_uri` is set).
+    #[payable]
+    pub fn nft_mint_in_folder(
+        &mut self,
+        token_id: TokenId,
+        receiver_id: AccountId,
+        folder: String,
+        token_metadata: TokenMetadata,
+    ) -> Token {
+        assert!(
+            !folder.is_empty() && !folder.contains('/') && folder != "." && folder != "..",
+            "Invalid folder name"
+        );
+        let path = match self.metadata.get().and_then(|metadata| metadata.base_uri) {
+            Some(base_uri) => format!("{}/{}/{}", base_uri.trim_end_matches('/'), folder, token_id),
+            None => format!("{}/{}", folder, token_id),
+        };
+        let mut token_metadata = token_metadata;
+        if token_metadata.media.is_none() {
+            token_metadata.media = Some(path.clone());
+        }
+        if token_metadata.reference.is_none() {
+            token_metadata.reference = Some(path);
+        }
+        self.nft_mint(token_id, receiver_id, token_metadata)
+    }
+
+    /// Gas used by the most recent mint up to the point it finished, for tuning metadata size.
+    pub fn debug_last_mint_gas(&self) -> u64 {
+        self.last_mint_gas
+    }
+
+    /// Sets metadata whose `title`, `description` and `media` are shown by `nft_token` for
+    /// tokens missing those fields. Stored token metadata is not changed.
+    #[payable]
+    pub fn set_fallback_metadata(&mut self, fallback_metadata: Option<TokenMetadata>) {
+        //Checks only contract owner can change the fallback metadata
+        self.assert_owner("Only Contract owner can change fallback metadata");
+        self.fallback_metadata = fallback_metadata;
+    }
+
+    pub fn get_fallback_metadata(&self) -> Option<TokenMetadata> {
+        self.fallback_metadata.clone()
+    }
+
+    /// Transfers `token_id` like `nft_transfer` and refunds the storage of every approval it
+    /// had to the previous owner, so the sale leaves no approval behind.
+    #[payable]
+    pub fn nft_transfer_clear_approvals(
+        &mut self,
+        token_id: TokenId,
+        receiver_id: AccountId,
+        memo: Option<String>,
+    ) {
+        self.assert_transferable(&token_id);
+        self.prune_expired_lock(&token_id);
+        assert_one_yocto();
+        let sender_id = env::predecessor_account_id();
+        let receiver_was_holder = self.is_holder(&receiver_id);
+        let (previous_owner_id, approved_account_ids) =
+            self.tokens
+                .internal_transfer(&sender_id, &receiver_id, &token_id, None, memo);
+        self.update_holder_count(&previous_owner_id, true);
+        self.update_holder_count(&receiver_id, receiver_was_holder);
+        if let Some(approved_account_ids) = approved_account_ids {
+            refund_approved_account_ids(previous_owner_id.clone(), &approved_account_ids);
+        }
+        self.notify_transfer_hook(token_id, previous_owner_id, receiver_id);
+    }
+
+    /// Ids of the tokens minted by `account_id`, in mint order, whoever owns them now. Ids that
+    /// were burned and minted again by another account are skipped, so a page may hold fewer
+    /// than `limit` entries.
+    pub fn nft_tokens_minted_by(
+        &self,
+        account_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<TokenId> {
+        match self.minted_by.get(&account_id) {
+            Some(minted_by) => minted_by
+                .iter()
+                .skip(from_index.map_or(0, |index| index.0 as usize))
+                .take(limit.map_or(usize::MAX, |limit| limit as usize))
+                .filter(|token_id| self.token_minters.get(token_id).as_ref() == Some(&account_id))
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Sets the account allowed to lift vesting locks through `unlock_token_by_oracle`.
+    #[payable]
+    pub fn set_oracle(&mut self, oracle: Option<AccountId>) {
+        //Checks only contract owner can change the oracle
+        self.assert_owner("Only Contract owner can change oracle");
+        self.oracle = oracle;
+    }
+
+    pub fn get_oracle(&self) -> Option<AccountId> {
+        self.oracle.clone()
+    }
+
+    /// Lifts the transfer lock of `token_id`. Callable only by the oracle account.
+    pub fn unlock_token_by_oracle(&mut self, token_id: TokenId) {
+        assert!(
+            self.oracle.as_ref() == Some(&env::predecessor_account_id()),
+            "Only the oracle can unlock tokens"
+        );
+        let owner_id = self.tokens.owner_by_id.get(&token_id);
+        assert!(
+            owner_id.is_some_and(|owner_id| self.internal_unlock(&owner_id, &token_id)),
+            "Token is not locked"
+        );
+    }
+
+    /// Lists `(token_id, current_owner)` in mint order. Pagination runs over every mint, so
+    /// burned tokens are skipped and a page may hold fewer than `limit` entries.
+    pub fn nft_tokens_chrono(
+        &self,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<(TokenId, AccountId)> {
+        self.mint_order
+            .iter()
+            .skip(from_index.map_or(0, |index| index.0 as usize))
+            .take(limit.map_or(usize::MAX, |limit| limit as usize))
+            .filter_map(|token_id| {
+                let owner_id = self.tokens.owner_by_id.get(&token_id)?;
+                Some((token_id, owner_id))
+            })
+            .collect()
+    }
+
+    /// Points each token at its new `media` URL. Any storage growth is charged once for the
+    /// whole batch against the attached deposit.
+    #[payable]
+    pub fn set_media_batch(&mut self, entries: Vec<(TokenId, String)>) {
+        //Checks only contract owner can change token media
+        self.assert_owner("Only Contract owner can change token media");
+        self.assert_field_not_frozen("media");
+        self.assert_field_not_frozen("media_hash");
+        assert!(
+            entries.len() as u64 <= self.batch_limits.media,
+            "Batch exceeds maximum of {} tokens",
+            self.batch_limits.media
+        );
+        let initial_storage_usage = env::storage_usage();
+        let token_metadata_by_id = self
+            .tokens
+            .token_metadata_by_id
+            .as_mut()
+            .unwrap_or_else(|| env::panic_str("Token metadata is not supported"));
+        for (token_id, media) in entries {
+            let mut metadata = token_metadata_by_id
+                .get(&token_id)
+                .unwrap_or_else(|| env::panic_str("Token not found"));
+            metadata.media = Some(media);
+            // the old hash no longer describes the new media
+            metadata.media_hash = None;
+            token_metadata_by_id.insert(&token_id, &metadata);
+        }
+        let storage_usage = env::storage_usage();
+        if storage_usage > initial_storage_usage {
+            refund_deposit(storage_usage - initial_storage_usage);
+        }
+    }
+
+    /// Mints `token_id` with empty metadata, subject to the same rules as `nft_mint`. The owner
+    /// fills it in later with `assign_deferred_metadata`.
+    #[payable]
+    pub fn nft_mint_deferred(&mut self, token_id: TokenId, receiver_id: AccountId) -> Token {
+        let token_metadata = TokenMetadata {
+            title: None,
+            description: None,
+            media: None,
+            media_hash: None,
+            copies: None,
+            issued_at: None,
+            expires_at: None,
+            starts_at: None,
+            updated_at: None,
+            extra: None,
+            reference: None,
+            reference_hash: None,
+        };
+        self.assert_mint_allowed(&token_metadata);
+        let initial_storage_usage = env::storage_usage();
+        let token = self.internal_mint(token_id, receiver_id, token_metadata, None);
+        self.deferred_tokens.insert(&token.token_id);
+        refund_deposit(env::storage_usage() - initial_storage_usage);
+        token
+    }
+
+    /// Assigns `metadatas[i]` to the deferred token `token_ids[i]`. Storage growth is charged once
+    /// for the whole batch against the attached deposit.
+    #[payable]
+    pub fn assign_deferred_metadata(
+        &mut self,
+        token_ids: Vec<TokenId>,
+        metadatas: Vec<TokenMetadata>,
+    ) {
+        //Checks only contract owner can assign deferred metadata
+        self.assert_owner("Only Contract owner can assign deferred metadata");
+        assert!(
+            token_ids.len() == metadatas.len(),
+            "token_ids and metadatas must have the same length"
+        );
+        assert!(
+            token_ids.len() as u64 <= self.batch_limits.deferred_metadata,
+            "Batch exceeds maximum of {} tokens",
+            self.batch_limits.deferred_metadata
+        );
+        let initial_storage_usage = env::storage_usage();
+        for (token_id, token_metadata) in token_ids.iter().zip(metadatas) {
+            assert!(
+                self.deferred_tokens.remove(token_id),
+                "Token has no deferred metadata: {}",
+                token_id
+            );
+            if let Some(reason) = self.token_metadata_error(&token_metadata) {
+                env::panic_str(&reason)
+            }
+            for token_trait in token_traits(&token_metadata) {
+                let count = self.trait_counts.get(&token_trait).unwrap_or(0);
+                self.trait_counts.insert(&token_trait, &(count + 1));
+            }
+            if let Some(token_metadata_by_id) = &mut self.tokens.token_metadata_by_id {
+                token_metadata_by_id.insert(token_id, &token_metadata);
+            }
+        }
+        let storage_usage = env::storage_usage();
+        if storage_usage > initial_storage_usage {
+            refund_deposit(storage_usage - initial_storage_usage);
+        }
+    }
+
+    pub fn is_metadata_deferred(&self, token_id: TokenId) -> bool {
+        self.deferred_tokens.contains(&token_id)
+    }
+
+    /// Permanently gives up ownership: every owner-only method panics afterwards and the owner
+    /// no longer bypasses mint gating. This cannot be undone.
+    #[payable]
+    pub fn renounce_ownership(&mut self) {
+        //Checks only contract owner can renounce ownership
+        self.assert_owner("Only Contract owner can renounce ownership");
+        self.ownership_renounced = true;
+        self.emit_admin_event("ownership_renounced", json!({}));
+    }
+
+    pub fn is_ownership_renounced(&self) -> bool {
+        self.ownership_renounced
+    }
+
+    /// Sets the account notified through `on_nft_transferred` after every transfer, or through
+    /// `on_nft_transferred_batch` once per `admin_transfer_all` call.
+    #[payable]
+    pub fn set_transfer_hook(&mut self, transfer_hook: Option<AccountId>) {
+        //Checks only contract owner can change the transfer hook
+        self.assert_owner("Only Contract owner can change transfer hook");
+        self.transfer_hook = transfer_hook;
+    }
+
+    pub fn get_transfer_hook(&self) -> Option<AccountId> {
+        self.transfer_hook.clone()
+    }
+
+    /// Permanently freezes one `TokenMetadata` field (e.g. `"media"`) for every token, while the
+    /// other fields stay editable. This cannot be undone.
+    #[payable]
+    pub fn freeze_field(&mut self, field: String) {
+        //Checks only contract owner can freeze metadata fields
+        self.assert_owner("Only Contract owner can freeze metadata fields");
+        assert!(
+            TOKEN_METADATA_FIELDS.contains(&field.as_str()),
+            "Unknown metadata field: {}",
+            field
+        );
+        if self.frozen_fields.insert(&field) {
+            self.emit_admin_event("field_frozen", json!({ "field": field }));
+        }
+    }
+
+    pub fn get_frozen_fields(&self) -> Vec<String> {
+        self.frozen_fields.to_vec()
+    }
+
+    /// Sets how long after a burn its id may be minted again. `None` never allows reuse.
+    #[payable]
+    pub fn set_allow_reuse_after_burn(&mut self, allow_reuse_after_burn_ns: Option<u64>) {
+        //Checks only contract owner can change burned id reuse
+        self.assert_owner("Only Contract owner can change burned id reuse");
+        self.allow_reuse_after_burn_ns = allow_reuse_after_burn_ns;
+    }
+
+    pub fn get_allow_reuse_after_burn(&self) -> Option<u64> {
+        self.allow_reuse_after_burn_ns
+    }
+
+    /// Block timestamp at which `token_id` was burned, if it was and has not been reminted.
+    pub fn get_burned_at(&self, token_id: TokenId) -> Option<u64> {
+        self.burned_at.get(&token_id)
+    }
+
+    /// Sets the content rating that applies to every token without its own override.
+    #[payable]
+    pub fn set_content_rating(&mut self, content_rating: Option<String>) {
+        //Checks only contract owner can change the content rating
+        self.assert_owner("Only Contract owner can change content rating");
+        self.content_rating = content_rating;
+    }
+
+    pub fn get_content_rating(&self) -> Option<String> {
+        self.content_rating.clone()
+    }
+
+    /// Overrides the collection content rating for `token_id`. `None` removes the override.
+    #[payable]
+    pub fn set_token_content_rating(&mut self, token_id: TokenId, content_rating: Option<String>) {
+        //Checks only contract owner can change token content ratings
+        self.assert_owner("Only Contract owner can change token content rating");
+        assert!(
+            self.tokens.owner_by_id.contains_key(&token_id),
+            "Token not found"
+        );
+        match content_rating {
+            Some(content_rating) => self
+                .token_content_ratings
+                .insert(&token_id, &content_rating),
+            None => self.token_content_ratings.remove(&token_id),
+        };
+    }
+
+    /// Content rating of `token_id`: its own override, or else the collection rating.
+    pub fn get_token_content_rating(&self, token_id: TokenId) -> Option<String> {
+        self.token_content_ratings
+            .get(&token_id)
+            .or_else(|| self.content_rating.clone())
+    }
+
+    /// Records holder balances into a snapshot, reading up to `limit` (capped at
+    /// `batch_limits.snapshot`) tokens. A call without `from_index` starts a new snapshot; later
+    /// calls continue the latest one from where it stopped and pass the number of tokens it has
+    /// read, as reported by `get_snapshot_progress`. Returns the holders first seen in this call
+    /// with their balances then.
+    #[payable]
+    pub fn snapshot_holders(
+        &mut self,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<(AccountId, U128)> {
+        //Checks only contract owner can snapshot holders
+        self.assert_owner("Only Contract owner can snapshot holders");
+        if from_index.is_none() {
+            let snapshot_id = self.snapshots.len();
+            self.snapshots.push(&HolderSnapshot {
+                holders: UnorderedMap::new(StorageKey::SnapshotHolders { snapshot_id }),
+                cursor: None,
+                tokens_read: 0,
+            });
+        }
+        let snapshot_id = self
+            .latest_snapshot_id()
+            .unwrap_or_else(|| panic!("No snapshot has been started"));
+        let mut snapshot = self.snapshots.get(snapshot_id).unwrap();
+        if let Some(from_index) = from_index {
+            assert!(
+                from_index.0 == snapshot.tokens_read as u128,
+                "Snapshot resumes at index {}",
+                snapshot.tokens_read
+            );
+        }
+        let limit = limit
+            .unwrap_or(self.batch_limits.snapshot)
+            .min(self.batch_limits.snapshot) as usize;
+        let page: Vec<(TokenId, AccountId)> = match snapshot.cursor.clone() {
+            Some(cursor) => self
+                .tokens
+                .owner_by_id
+                .iter_from(cursor)
+                .take(limit)
+                .collect(),
+            None => self.tokens.owner_by_id.iter().take(limit).collect(),
+        };
+        let mut recorded = vec![];
+        for (token_id, owner_id) in page {
+            if snapshot.holders.get(&owner_id).is_none() {
+                let balance = self.nft_supply_for_owner(owner_id.clone()).0 as u64;
+                snapshot.holders.insert(&owner_id, &balance);
+                recorded.push((owner_id, U128(balance as u128)));
+            }
+            snapshot.cursor = Some(token_id);
+            snapshot.tokens_read += 1;
+        }
+        self.snapshots.replace(snapshot_id, &snapshot);
+        recorded
+    }
+
+    /// Id of the snapshot `snapshot_holders` is filling, if any was started.
+    pub fn latest_snapshot_id(&self) -> Option<u64> {
+        self.snapshots.len().checked_sub(1)
+    }
+
+    /// Number of tokens `snapshot_id` has read so far, the `from_index` of its next page.
+    pub fn get_snapshot_progress(&self, snapshot_id: u64) -> Option<U128> {
+        self.snapshots
+            .get(snapshot_id)
+            .map(|snapshot| U128(snapshot.tokens_read as u128))
+    }
+
+    pub fn get_snapshot(
+        &self,
+        snapshot_id: u64,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<(AccountId, U128)> {
+        match self.snapshots.get(snapshot_id) {
+            Some(snapshot) => snapshot
+                .holders
+                .iter()
+                .skip(from_index.map_or(0, |index| index.0 as usize))
+                .take(limit.map_or(usize::MAX, |limit| limit as usize))
+                .map(|(account_id, balance)| (account_id, U128(balance as u128)))
+                .collect(),
+            None => vec![],
+        }
+    }
+}
+
+impl Contract {
+    /// Builds the full state around `base`, with every later field at its default.
+    fn from_base(base: BaseState) -> Self {
+        Self {
+            tokens: base.tokens,
+            metadata: base.metadata,
+            mint_approval_status: base.mint_approval_status,
+            whitelist_accounts: base.whitelist_accounts,
+            max_attached_deposit: None,
+            whitelist_window: None,
+            require_reference_hash: false,
+            approval_rate_limit: None,
+            approvals_this_window: LookupMap::new(StorageKey::ApprovalsThisWindow),
+            token_unlocks: UnorderedMap::new(StorageKey::TokenUnlocks),
+            locked_by_owner: LookupMap::new(StorageKey::LockedByOwner),
+            unlock_counts: TreeMap::new(StorageKey::UnlockCounts),
+            soulbound_tokens: LookupSet::new(StorageKey::SoulboundTokens),
+            soulbound_frozen: false,
+            collection_description: None,
+            paused_until_ns: None,
+            token_minters: LookupMap::new(StorageKey::TokenMinters),
+            event_standard: DEFAULT_EVENT_STANDARD.to_string(),
+            status_change_cooldown_ns: 0,
+            last_status_change_ns: None,
+            total_minted: 0,
+            total_burned: 0,
+            holder_count: 0,
+            mint_deadline_ns: None,
+            duplicate_whitelist_policy: Policy::Ignore,
+            supply_history: vec![],
+            renderers: LookupMap::new(StorageKey::Renderers),
+            owner_minting_paused: false,
+            trait_counts: LookupMap::new(StorageKey::TraitCounts),
+            pending_recipients: LookupMap::new(StorageKey::PendingRecipients),
+            pending_by_recipient: LookupMap::new(StorageKey::PendingByRecipient),
+            compliance_accounts: vec![],
+            batch_limits: BatchLimits {
+                id_gap_range: MAX_ID_GAP_RANGE,
+                admin_transfer: MAX_ADMIN_TRANSFER_BATCH,
+                soulbound: MAX_SOULBOUND_BATCH,
+                media: MAX_MEDIA_BATCH,
+                deferred_metadata: MAX_DEFERRED_METADATA_BATCH,
+                exist: MAX_EXIST_BATCH,
+                snapshot: MAX_SNAPSHOT_BATCH,
+                approvals: MAX_APPROVALS_BATCH,
+                references: MAX_REFERENCES_BATCH,
+            },
+            last_mint_gas: 0,
+            fallback_metadata: None,
+            minted_by: LookupMap::new(StorageKey::MintedBy),
+            oracle: None,
+            mint_order: Vector::new(StorageKey::MintOrder),
+            deferred_tokens: LookupSet::new(StorageKey::DeferredTokens),
+            ownership_renounced: false,
+            transfer_hook: None,
+            frozen_fields: UnorderedSet::new(StorageKey::FrozenFields),
+            allow_reuse_after_burn_ns: None,
+            burned_at: LookupMap::new(StorageKey::BurnedAt),
+            content_rating: None,
+            token_content_ratings: LookupMap::new(StorageKey::TokenContentRatings),
+            snapshots: Vector::new(StorageKey::Snapshots),
+        }
+    }
+
+    /// Panics with `message` unless called by the contract account and ownership has not been
+    /// renounced.
+    fn assert_owner(&self, message: &str) {
+        assert!(!self.ownership_renounced, "Ownership has been renounced");
+        assert!(
+            env::current_account_id() == env::predecessor_account_id(),
+            "{}",
+            message
+        );
+    }
+
+    fn record_approval(&mut self, account_id: &AccountId) {
+        let (window_ns, max_approvals) = match self.approval_rate_limit {
+            Some(limit) => limit,
+            None => return,
+        };
+        let window = env::block_timestamp() / window_ns;
+        let count = match self.approvals_this_window.get(account_id) {
+            Some((last_window, count)) if last_window == window => count,
+            _ => 0,
+        };
+        assert!(count < max_approvals, "Approval rate limit exceeded");
+        self.approvals_this_window
+            .insert(account_id, &(window, count + 1));
+    }
+
+    /// Mints the token and records contract-specific per-token state. If `refund_id` is given,
+    /// the storage of both is charged to the attached deposit and the rest is refunded to it;
+    /// otherwise the caller is responsible for settling storage.
+    fn internal_mint(
+        &mut self,
+        token_id: TokenId,
+        receiver_id: AccountId,
+        token_metadata: TokenMetadata,
+        refund_id: Option<AccountId>,
+    ) -> Token {
+        let reused = match self.burned_at.get(&token_id) {
+            Some(burned_ns) => {
+                match self.allow_reuse_after_burn_ns {
+                    Some(grace_ns) => assert!(
+                        env::block_timestamp() >= burned_ns.saturating_add(grace_ns),
+                        "Burned token id can be reused from {}",
+                        burned_ns.saturating_add(grace_ns)
+                    ),
+                    None => panic!("Burned token id cannot be reused"),
+                }
+                self.burned_at.remove(&token_id);
+                true
+            }
+            None => false,
+        };
+        let initial_storage_usage = env::storage_usage();
+        let receiver_was_holder = self.is_holder(&receiver_id);
+        let token = self.tokens.internal_mint_with_refund(
+            token_id,
+            receiver_id,
+            Some(token_metadata),
+            None,
+        );
+        self.update_holder_count(&token.owner_id, receiver_was_holder);
+        let minter = env::predecessor_account_id();
+        // a burned id reminted by its original minter is already in that minter's list
+        if self.token_minters.insert(&token.token_id, &minter).as_ref() != Some(&minter) {
+            let mut minted_by = self.minted_by.get(&minter).unwrap_or_else(|| {
+                Vector::new(StorageKey::MintedByAccount {
+                    account_hash: env::sha256(minter.as_bytes()),
+                })
+            });
+            minted_by.push(&token.token_id);
+            self.minted_by.insert(&minter, &minted_by);
+        }
+        // a reused id keeps its original position in the mint order
+        if !reused {
+            self.mint_order.push(&token.token_id);
+        }
+        for token_trait in token
+            .metadata
+            .as_ref()
+            .map(token_traits)
+            .unwrap_or_default()
+        {
+            let count = self.trait_counts.get(&token_trait).unwrap_or(0);
+            self.trait_counts.insert(&token_trait, &(count + 1));
+        }
+        self.total_minted += 1;
+        self.record_supply_history();
+        if let Some(refund_id) = refund_id {
+            refund_deposit_to_account(env::storage_usage() - initial_storage_usage, refund_id);
+        }
+        NftMint {
+            owner_id: &token.owner_id,
+            token_ids: &[&token.token_id],
+            memo: None,
+        }
+        .emit();
+        // diagnostic only: costs one small write per mint
+        self.last_mint_gas = env::used_gas().0;
+        token
+    }
+
+    /// Upper bound on the bytes `internal_mint` stores for a token minted by `minter_id`, for
+    /// token ids of up to 64 characters like `extra_storage_in_bytes_per_token`.
+    fn mint_storage_bytes(&self, minter_id: &AccountId, token_metadata: &TokenMetadata) -> u64 {
+        // plain StorageKey variants serialize to a single byte
+        let prefix_bytes = 1;
+        let token_id_bytes = "a".repeat(64).try_to_vec().unwrap().len() as u64;
+        let minter_bytes = minter_id.try_to_vec().unwrap().len() as u64;
+        let minted_by_prefix_bytes = StorageKey::MintedByAccount {
+            account_hash: env::sha256(minter_id.as_bytes()),
+        }
+        .try_to_vec()
+        .unwrap()
+        .len() as u64;
+        // owner, metadata and enumeration records measured by the library, plus the metadata
+        let mut bytes = self.tokens.extra_storage_in_bytes_per_token
+            + token_metadata.try_to_vec().unwrap().len() as u64;
+        // token_minters
+        bytes += prefix_bytes + token_id_bytes + minter_bytes + STORAGE_BYTES_PER_RECORD;
+        // minted_by: the minter's vector when it is new, and its new element
+        if !self.minted_by.contains_key(minter_id) {
+            // Vector stores its length and its prefix
+            bytes += prefix_bytes + minter_bytes + 8 + 4 + minted_by_prefix_bytes;
+            bytes += STORAGE_BYTES_PER_RECORD;
+        }
+        bytes += minted_by_prefix_bytes + 8 + token_id_bytes + STORAGE_BYTES_PER_RECORD;
+        // mint_order
+        bytes += prefix_bytes + 8 + token_id_bytes + STORAGE_BYTES_PER_RECORD;
+        // trait_counts entries for traits no token has yet
+        for token_trait in token_traits(token_metadata) {
+            if !self.trait_counts.contains_key(&token_trait) {
+                bytes += prefix_bytes + token_trait.try_to_vec().unwrap().len() as u64 + 8;
+                bytes += STORAGE_BYTES_PER_RECORD;
+            }
+        }
+        // supply_history gains a (block_height, total_minted, total_burned) entry until it is full
+        if self.supply_history.len() < SUPPLY_HISTORY_SIZE {
+            bytes += 24;
+        }
+        bytes
+    }
+
+    /// Panics unless the predecessor may mint a token with `token_metadata` right now.
+    fn assert_mint_allowed(&self, token_metadata: &TokenMetadata) {
+        // cap on attached deposit applies to every mint, owner included
+        if let Some(max_attached_deposit) = &self.max_attached_deposit {
+            assert!(
+                env::attached_deposit() <= max_attached_deposit.0,
+                "Deposit exceeds maximum"
+            );
+        }
+        if let Some(reason) = self.token_metadata_error(token_metadata) {
+            env::panic_str(&reason)
+        }
+        if let Some(reason) = self.mint_denial_reason(&env::predecessor_account_id()) {
+            env::panic_str(&reason)
+        }
+    }
+
+    /// Why `account_id` may not call `nft_mint` right now, or `None` if it may.
+    fn mint_denial_reason(&self, account_id: &AccountId) -> Option<String> {
+        // the deadline binds every minter, owner included
+        if let Some(mint_deadline_ns) = self.mint_deadline_ns {
+            if env::block_timestamp() >= mint_deadline_ns {
+                return Some("Mint deadline passed".to_string());
+            }
+        }
+        // owner can mint nft irrespective of mint_approval_status, unless it gave that up
+        if account_id == &env::current_account_id()
+            && !self.owner_minting_paused
+            && !self.ownership_renounced
+        {
+            return None;
+        }
+        if self.is_paused() {
+            return Some("Contract is paused".to_string());
+        }
+        match self.mint_approval_status {
+            Status::All => None,
+            Status::Whitelist => {
+                if !self.whitelist_accounts.contains(account_id) {
+                    return Some("Only Whitelist Accounts can mint NFT".to_string());
+                }
+                if let Some((start, end)) = self.whitelist_window {
+                    let now = env::block_timestamp();
+                    if now < start || now >= end {
+                        return Some("Whitelist minting window is closed".to_string());
+                    }
+                }
+                None
+            }
+            Status::None => Some("Minting is not allowed for Now".to_string()),
+        }
+    }
+
+    fn token_metadata_error(&self, token_metadata: &TokenMetadata) -> Option<String> {
+        if self.require_reference_hash
+            && token_metadata.reference.is_some()
+            && token_metadata.reference_hash.is_none()
+        {
+            return Some("reference_hash is required when reference is set".to_string());
+        }
+        None
+    }
+
+    /// Business-rule checks for transfers (pause, escrow, soulbound, vesting lock). Transfer
+    /// wrappers call this before delegating to `self.tokens`, so a rejection panics before any
+    /// state change and the attached yoctoNEAR is returned with the failed receipt.
+    fn assert_transferable(&self, token_id: &TokenId) {
+        assert!(!self.is_paused(), "Contract is paused");
+        // escrowed tokens only leave the contract through approve_pending or reject_pending
+        assert!(
+            !self.pending_recipients.contains_key(token_id),
+            "Token is pending compliance review"
+        );
+        assert!(
+            !self.soulbound_tokens.contains(token_id),
+            "Token is soulbound"
+        );
+        if let Some(unlock_ns) = self.token_unlocks.get(token_id) {
+            assert!(
+                env::block_timestamp() >= unlock_ns,
+                "Token is locked until {}",
+                unlock_ns
+            );
+        }
+    }
+
+    fn internal_set_approval_status(&mut self, approval_status: &str) {
+        let now = env::block_timestamp();
+        if let Some(last_status_change_ns) = self.last_status_change_ns {
+            assert!(
+                now >= last_status_change_ns.saturating_add(self.status_change_cooldown_ns),
+                "Approval status was changed too recently"
+            );
+        }
+        self.last_status_change_ns = Some(now);
+        match approval_status {
+            "all" => {
+                log!("NFT approval status is set to ALL");
+                self.mint_approval_status = Status::All
+            }
+            "whitelist" => {
+                log!("NFT approval status is set to Whitelist ");
+                self.mint_approval_status = Status::Whitelist
+            }
+            "none" => {
+                log!("NFT approval status is set to Nne ");
+                self.mint_approval_status = Status::None
+            }
+            _ => panic!("Invalid approval status: {}", approval_status),
+        };
+        self.emit_admin_event(
+            "approval_status_changed",
+            json!({ "approval_status": approval_status }),
+        );
+    }
+
+    /// Logs a NEP-297 event tagged with the configured `event_standard`.
+    fn emit_admin_event(&self, event: &str, data: Value) {
+        let event = json!({
+            "standard": self.event_standard,
+            "version": ADMIN_EVENT_VERSION,
+            "event": event,
+            "data": [data],
+        });
+        env::log_str(&format!("EVENT_JSON:{}", event));
+    }
+
+    /// Stores the current counters for this block, dropping the oldest entry once
+    /// `SUPPLY_HISTORY_SIZE` blocks are retained.
+    fn record_supply_history(&mut self) {
+        let block_height = env::block_height();
+        let entry = (block_height, self.total_minted, self.total_burned);
+        match self.supply_history.last_mut() {
+            Some(last) if last.0 == block_height => *last = entry,
+            _ => {
+                if self.supply_history.len() == SUPPLY_HISTORY_SIZE {
+                    self.supply_history.remove(0);
+                }
+                self.supply_history.push(entry);
+            }
+        }
+    }
+
+    fn assert_compliance(&self) {
+        let predecessor_account_id = env::predecessor_account_id();
+        assert!(
+            (predecessor_account_id == env::current_account_id() && !self.ownership_renounced)
+                || self.compliance_accounts.contains(&predecessor_account_id),
+            "Only Contract owner or compliance accounts can review pending mints"
+        );
+    }
+
+    /// Removes the token and all per-token state kept for it, and emits the burn event. Its
+    /// minter is kept so that `nft_tokens_minted_by` stays consistent if the id is reminted.
+    fn internal_burn(&mut self, token_id: &TokenId) {
+        let owner_id = self
+            .tokens
+            .owner_by_id
+            .remove(token_id)
+            .unwrap_or_else(|| env::panic_str("Token not found"));
+        let metadata = self
+            .tokens
+            .token_metadata_by_id
+            .as_mut()
+            .and_then(|by_id| by_id.remove(token_id));
+        if let Some(tokens_per_owner) = &mut self.tokens.tokens_per_owner {
+            if let Some(mut owner_tokens) = tokens_per_owner.get(&owner_id) {
+                owner_tokens.remove(token_id);
+                if owner_tokens.is_empty() {
+                    tokens_per_owner.remove(&owner_id);
+                } else {
+                    tokens_per_owner.insert(&owner_id, &owner_tokens);
+                }
+            }
+        }
+        self.update_holder_count(&owner_id, true);
+        if let Some(approvals_by_id) = &mut self.tokens.approvals_by_id {
+            approvals_by_id.remove(token_id);
+        }
+        if let Some(next_approval_id_by_id) = &mut self.tokens.next_approval_id_by_id {
+            next_approval_id_by_id.remove(token_id);
+        }
+        for token_trait in metadata.as_ref().map(token_traits).unwrap_or_default() {
+            match self.trait_counts.get(&token_trait).unwrap_or(0) {
+                0 | 1 => self.trait_counts.remove(&token_trait),
+                count => self.trait_counts.insert(&token_trait, &(count - 1)),
+            };
+        }
+        self.internal_unlock(&owner_id, token_id);
+        self.soulbound_tokens.remove(token_id);
+        self.renderers.remove(token_id);
+        self.token_content_ratings.remove(token_id);
+        self.deferred_tokens.remove(token_id);
+        self.burned_at.insert(token_id, &env::block_timestamp());
+        self.total_burned += 1;
+        self.record_supply_history();
+        NftBurn {
+            owner_id: &owner_id,
+            token_ids: &[token_id],
+            authorized_id: None,
+            memo: None,
+        }
+        .emit();
+    }
+
+    /// Fires a detached `on_nft_transferred` call to the transfer hook, if one is set.
+    /// The promise is not awaited, so a failing hook never reverts the transfer.
+    fn notify_transfer_hook(&self, token_id: TokenId, old_owner: AccountId, new_owner: AccountId) {
+        if let Some(hook) = self.transfer_hook.clone() {
+            ext_transfer_hook::ext(hook)
+                .with_static_gas(GAS_FOR_TRANSFER_HOOK)
+                .on_nft_transferred(token_id, old_owner, new_owner);
+        }
+    }
+
+    /// Like `notify_transfer_hook`, with a single `on_nft_transferred_batch` call for tokens
+    /// moved together, so a whole batch fits in one call's gas.
+    fn notify_transfer_hook_batch(
+        &self,
+        token_ids: Vec<TokenId>,
+        old_owner: AccountId,
+        new_owner: AccountId,
+    ) {
+        if let Some(hook) = self.transfer_hook.clone() {
+            ext_transfer_hook::ext(hook)
+                .with_static_gas(GAS_FOR_TRANSFER_HOOK)
+                .on_nft_transferred_batch(token_ids, old_owner, new_owner);
+        }
+    }
+
+    fn assert_field_not_frozen(&self, field: &str) {
+        assert!(
+            !self.frozen_fields.contains(&field.to_string()),
+            "Metadata field is frozen: {}",
+            field
+        );
+    }
+
+    /// Drops `token_id` from escrow and returns its intended recipient.
+    fn internal_remove_pending(&mut self, token_id: &TokenId) -> AccountId {
+        let receiver_id = self
+            .pending_recipients
+            .remove(token_id)
+            .unwrap_or_else(|| env::panic_str("Token is not pending"));
+        if let Some(mut pending) = self.pending_by_recipient.get(&receiver_id) {
+            pending.remove(token_id);
+            if pending.is_empty() {
+                self.pending_by_recipient.remove(&receiver_id);
+            } else {
+                self.pending_by_recipient.insert(&receiver_id, &pending);
+            }
+        }
+        receiver_id
+    }
+
+    /// Fills the display fields missing from `token`'s metadata from `fallback_metadata`.
+    fn with_fallback_metadata(&self, mut token: Token) -> Token {
+        if let (Some(metadata), Some(fallback)) = (&mut token.metadata, &self.fallback_metadata) {
+            apply_fallback_metadata(metadata, fallback);
+        }
+        token
+    }
+
+    fn is_holder(&self, account_id: &AccountId) -> bool {
+        self.tokens
+            .tokens_per_owner
+            .as_ref()
+            .and_then(|by_owner| by_owner.get(account_id))
+            .is_some_and(|owner_tokens| !owner_tokens.is_empty())
+    }
+
+    /// Keeps `holder_count` in step after the balance of `account_id` changed, given whether it
+    /// held any token before the change.
+    fn update_holder_count(&mut self, account_id: &AccountId, was_holder: bool) {
+        match (was_holder, self.is_holder(account_id)) {
+            (false, true) => self.holder_count += 1,
+            (true, false) => self.holder_count -= 1,
+            _ => {}
+        }
+    }
+
+    /// Locks `token_id`, held by `owner_id`, against transfers until `unlock_ns`.
+    fn internal_lock(&mut self, owner_id: &AccountId, token_id: &TokenId, unlock_ns: u64) {
+        self.token_unlocks.insert(token_id, &unlock_ns);
+        self.index_lock(owner_id, token_id);
+        let count = self.unlock_counts.get(&unlock_ns).unwrap_or(0);
+        self.unlock_counts.insert(&unlock_ns, &(count + 1));
+    }
+
+    /// Drops the lock of `token_id`, held by `owner_id`. Returns whether it was locked.
+    fn internal_unlock(&mut self, owner_id: &AccountId, token_id: &TokenId) -> bool {
+        match self.token_unlocks.remove(token_id) {
+            Some(unlock_ns) => {
+                self.unindex_lock(owner_id, token_id);
+                match self.unlock_counts.get(&unlock_ns).unwrap_or(0) {
+                    0 | 1 => self.unlock_counts.remove(&unlock_ns),
+                    count => self.unlock_counts.insert(&unlock_ns, &(count - 1)),
+                };
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drops the lock of `token_id` once it has expired, so it is not carried to the next owner.
+    fn prune_expired_lock(&mut self, token_id: &TokenId) {
+        let expired = self
+            .token_unlocks
+            .get(token_id)
+            .is_some_and(|unlock_ns| env::block_timestamp() >= unlock_ns);
+        if expired {
+            if let Some(owner_id) = self.tokens.owner_by_id.get(token_id) {
+                self.internal_unlock(&owner_id, token_id);
+            }
+        }
+    }
+
+    fn index_lock(&mut self, owner_id: &AccountId, token_id: &TokenId) {
+        let mut locked = self.locked_by_owner.get(owner_id).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::LockedByOwnerAccount {
+                account_hash: env::sha256(owner_id.as_bytes()),
+            })
+        });
+        locked.insert(token_id);
+        self.locked_by_owner.insert(owner_id, &locked);
+    }
+
+    fn unindex_lock(&mut self, owner_id: &AccountId, token_id: &TokenId) {
+        if let Some(mut locked) = self.locked_by_owner.get(owner_id) {
+            locked.remove(token_id);
+            if locked.is_empty() {
+                self.locked_by_owner.remove(owner_id);
+            } else {
+                self.locked_by_owner.insert(owner_id, &locked);
+            }
+        }
+    }
+}
+
+#[near_bindgen]
+impl NonFungibleTokenCore for Contract {
+    #[payable]
+    fn nft_transfer(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    ) {
+        self.assert_transferable(&token_id);
+        self.prune_expired_lock(&token_id);
+        let old_owner = self.tokens.owner_by_id.get(&token_id);
+        let receiver_was_holder = self.is_holder(&receiver_id);
+        self.tokens
+            .nft_transfer(receiver_id.clone(), token_id.clone(), approval_id, memo);
+        if let Some(old_owner) = old_owner {
+            self.update_holder_count(&old_owner, true);
+            self.update_holder_count(&receiver_id, receiver_was_holder);
+            self.notify_transfer_hook(token_id, old_owner, receiver_id);
+        }
+    }
+
+    #[payable]
+    fn nft_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<bool> {
+        self.assert_transferable(&token_id);
+        self.prune_expired_lock(&token_id);
+        // mirrors the library's nft_transfer_call so the resolver gets the budget set here
+        assert_one_yocto();
+        require!(
+            env::prepaid_gas() > GAS_FOR_NFT_TRANSFER_CALL,
+            "More gas is required"
+        );
+        let sender_id = env::predecessor_account_id();
+        let receiver_was_holder = self.is_holder(&receiver_id);
+        let (old_owner, old_approvals) =
+            self.tokens
+                .internal_transfer(&sender_id, &receiver_id, &token_id, approval_id, memo);
+        self.update_holder_count(&old_owner, true);
+        self.update_holder_count(&receiver_id, receiver_was_holder);
+        self.notify_transfer_hook(token_id.clone(), old_owner.clone(), receiver_id.clone());
+        ext_nft_receiver::ext(receiver_id.clone())
+            .with_static_gas(env::prepaid_gas() - GAS_FOR_NFT_TRANSFER_CALL)
+            .nft_on_transfer(sender_id, old_owner.clone(), token_id.clone(), msg)
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .nft_resolve_transfer(old_owner, receiver_id, token_id, old_approvals),
+            )
+            .into()
+    }
+
+    fn nft_token(&self, token_id: TokenId) -> Option<Token> {
+        self.tokens
+            .nft_token(token_id)
+            .map(|token| self.with_fallback_metadata(token))
+    }
+}
+
+#[near_bindgen]
+impl NonFungibleTokenResolver for Contract {
+    /// Returns true if token was successfully transferred to `receiver_id`.
+    ///
+    /// The library restores ownership and the previous owner's approvals when the receiver rejects
+    /// the token, and emits the corrective `nft_transfer` event; the transfer hook is told too.
+    #[private]
+    fn nft_resolve_transfer(
+        &mut self,
+        previous_owner_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approved_account_ids: Option<HashMap<AccountId, u64>>,
+    ) -> bool {
+        let previous_owner_was_holder = self.is_holder(&previous_owner_id);
+        let transferred = self.tokens.nft_resolve_transfer(
+            previous_owner_id.clone(),
+            receiver_id.clone(),
+            token_id.clone(),
+            approved_account_ids,
+        );
+        if !transferred {
+            self.update_holder_count(&receiver_id, true);
+            self.update_holder_count(&previous_owner_id, previous_owner_was_holder);
+            self.notify_transfer_hook(token_id, receiver_id, previous_owner_id);
+        }
+        transferred
+    }
+}
+
+#[near_bindgen]
+impl NonFungibleTokenApproval for Contract {
+    #[payable]
+    fn nft_approve(
+        &mut self,
+        token_id: TokenId,
+        account_id: AccountId,
+        msg: Option<String>,
+    ) -> Option<Promise> {
+        assert!(
+            !self.pending_recipients.contains_key(&token_id),
+            "Token is pending compliance review"
+        );
+        // contract owner is exempt from the approval rate limit
+        let predecessor_account_id = env::predecessor_account_id();
+        if predecessor_account_id != env::current_account_id() {
+            self.record_approval(&predecessor_account_id);
+        }
+        self.tokens.nft_approve(token_id, account_id, msg)
+    }
+
+    #[payable]
+    fn nft_revoke(&mut self, token_id: TokenId, account_id: AccountId) {
+        self.tokens.nft_revoke(token_id, account_id)
+    }
+
+    #[payable]
+    fn nft_revoke_all(&mut self, token_id: TokenId) {
+        self.tokens.nft_revoke_all(token_id)
+    }
+
+    fn nft_is_approved(
+        &self,
+        token_id: TokenId,
+        approved_account_id: AccountId,
+        approval_id: Option<u64>,
+    ) -> bool {
+        self.tokens
+            .nft_is_approved(token_id, approved_account_id, approval_id)
+    }
+}
+
+#[near_bindgen]
+impl NonFungibleTokenEnumeration for Contract {
+    fn nft_total_supply(&self) -> U128 {
+        self.tokens.nft_total_supply()
+    }
+
+    fn nft_tokens(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<Token> {
+        self.tokens
+            .nft_tokens(from_index, limit)
+            .into_iter()
+            .map(|token| self.with_fallback_metadata(token))
+            .collect()
+    }
+
+    fn nft_supply_for_owner(&self, account_id: AccountId) -> U128 {
+        self.tokens.nft_supply_for_owner(account_id)
+    }
+
+    fn nft_tokens_for_owner(
+        &self,
+        account_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<Token> {
+        self.tokens
+            .nft_tokens_for_owner(account_id, from_index, limit)
+            .into_iter()
+            .map(|token| self.with_fallback_metadata(token))
+            .collect()
+    }
+}
+
+#[near_bindgen]
+impl NonFungibleTokenMetadataProvider for Contract {
+    fn nft_metadata(&self) -> NFTContractMetadata {
+        self.metadata.get().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::json_types::Base64VecU8;
+    use near_sdk::mock::VmAction;
+    use near_sdk::test_utils::{accounts, get_created_receipts, get_logs, VMContextBuilder};
+    use near_sdk::{testing_env, Balance};
+
+    // `env::panic_str` aborts the test process instead of unwinding, so rejections raised
+    // through it are checked via `mint_requirements` rather than `#[should_panic]`.
+    const MINT_DEPOSIT: Balance = 100_000_000_000_000_000_000_000;
+    const APPROVAL_DEPOSIT: Balance = 10_000_000_000_000_000_000_000;
+
+    fn context(predecessor_account_id: AccountId) -> VMContextBuilder {
+        let mut builder = VMContextBuilder::new();
+        builder
+            .current_account_id(accounts(0))
+            .signer_account_id(predecessor_account_id.clone())
+            .predecessor_account_id(predecessor_account_id);
+        builder
+    }
+
+    fn set_context(predecessor_account_id: AccountId, attached_deposit: Balance) {
+        testing_env!(context(predecessor_account_id)
+            .attached_deposit(attached_deposit)
+            .build());
+    }
+
+    fn set_context_at(
+        predecessor_account_id: AccountId,
+        attached_deposit: Balance,
+        block_timestamp: u64,
+    ) {
+        testing_env!(context(predecessor_account_id)
+            .attached_deposit(attached_deposit)
+            .block_timestamp(block_timestamp)
+            .build());
+    }
+
+    fn new_contract() -> Contract {
+        set_context(accounts(0), 0);
+        Contract::new_default_meta(accounts(0))
+    }
+
+    fn token_metadata(title: &str) -> TokenMetadata {
+        TokenMetadata {
+            title: Some(title.to_string()),
+            description: None,
+            media: None,
+            media_hash: None,
+            copies: None,
+            issued_at: None,
+            expires_at: None,
+            starts_at: None,
+            updated_at: None,
+            extra: None,
+            reference: None,
+            reference_hash: None,
+        }
+    }
+
+    fn metadata_with_traits(traits: &[(&str, &str)]) -> TokenMetadata {
+        let attributes: Vec<Value> = traits
+            .iter()
+            .map(|(trait_type, value)| json!({ "trait_type": trait_type, "value": value }))
+            .collect();
+        TokenMetadata {
+            extra: Some(json!({ "attributes": attributes }).to_string()),
+            ..token_metadata("traits")
+        }
+    }
+
+    fn mint(contract: &mut Contract, token_id: &str, receiver_id: AccountId) -> Token {
+        set_context(accounts(0), MINT_DEPOSIT);
+        contract.nft_mint(token_id.to_string(), receiver_id, token_metadata(token_id))
+    }
+
+    fn transfer(contract: &mut Contract, from: AccountId, to: AccountId, token_id: &str) {
+        set_context(from, 1);
+        contract.nft_transfer(to, token_id.to_string(), None, None);
+    }
+
+    fn owner_of(contract: &Contract, token_id: &str) -> Option<AccountId> {
+        contract.tokens.owner_by_id.get(&token_id.to_string())
+    }
+
+    fn has_function_call(receiver_id: &AccountId, function_name: &str) -> bool {
+        get_created_receipts().iter().any(|receipt| {
+            &receipt.receiver_id == receiver_id
+                && receipt.actions.iter().any(|action| {
+                    matches!(action, VmAction::FunctionCall { function_name: name, .. } if name == function_name)
+                })
+        })
+    }
+
+    #[test]
+    fn mint_under_deposit_cap() {
+        let mut contract = new_contract();
+        contract.set_max_attached_deposit(Some(U128(MINT_DEPOSIT)));
+        mint(&mut contract, "1", accounts(1));
+        assert_eq!(owner_of(&contract, "1"), Some(accounts(1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Deposit exceeds maximum")]
+    fn mint_over_deposit_cap() {
+        let mut contract = new_contract();
+        contract.set_max_attached_deposit(Some(U128(MINT_DEPOSIT - 1)));
+        mint(&mut contract, "1", accounts(1));
+    }
+
+    #[test]
+    fn open_whitelist_phase_applies_list_window_and_status() {
+        let mut contract = new_contract();
+        contract.add_whitelist_account(accounts(3));
+        contract.open_whitelist_phase(vec![accounts(1), accounts(2)], Some((10, 20)));
+        assert_eq!(contract.get_whitelist_window(), Some((10, 20)));
+        assert!(matches!(contract.mint_approval_status, Status::Whitelist));
+        assert_eq!(contract.whitelist_accounts, vec![accounts(1), accounts(2)]);
+    }
+
+    #[test]
+    fn require_reference_hash_accepts_hashed_reference() {
+        let mut contract = new_contract();
+        contract.set_require_reference_hash(true);
+        set_context(accounts(0), MINT_DEPOSIT);
+        let token_metadata = TokenMetadata {
+            reference: Some("ipfs://reference".to_string()),
+            reference_hash: Some(Base64VecU8(env::sha256(b"reference"))),
+            ..token_metadata("1")
+        };
+        contract.nft_mint("1".to_string(), accounts(1), token_metadata);
+        assert!(contract.get_require_reference_hash());
+    }
+
+    #[test]
+    fn require_reference_hash_rejects_unhashed_reference() {
+        let mut contract = new_contract();
+        contract.set_require_reference_hash(true);
+        let token_metadata = TokenMetadata {
+            reference: Some("ipfs://reference".to_string()),
+            ..token_metadata("1")
+        };
+        assert_eq!(
+            contract
+                .mint_requirements(accounts(0), token_metadata)
+                .reason,
+            Some("reference_hash is required when reference is set".to_string())
+        );
+    }
+
+    #[test]
+    fn find_id_gaps_reports_burned_id() {
+        let mut contract = new_contract();
+        for token_id in ["1", "2", "3"] {
+            mint(&mut contract, token_id, accounts(1));
+        }
+        contract.internal_burn(&"2".to_string());
+        assert_eq!(contract.find_id_gaps(1, 4), vec![2, 4]);
+    }
+
+    #[test]
+    fn resolve_transfer_returns_rejected_token() {
+        let mut contract = new_contract();
+        mint(&mut contract, "1", accounts(1));
+        set_context(accounts(1), 1);
+        contract.nft_transfer_call(accounts(2), "1".to_string(), None, None, "".to_string());
+        assert_eq!(owner_of(&contract, "1"), Some(accounts(2)));
+
+        testing_env!(
+            context(accounts(0)).build(),
+            near_sdk::VMConfig::test(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![near_sdk::PromiseResult::Successful(b"true".to_vec())],
+        );
+        let transferred = contract.nft_resolve_transfer(
+            accounts(1),
+            accounts(2),
+            "1".to_string(),
+            Some(HashMap::new()),
+        );
+        assert!(!transferred);
+        assert_eq!(owner_of(&contract, "1"), Some(accounts(1)));
+        assert_eq!(contract.get_dashboard().holder_count, 1);
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.contains("\"event\":\"nft_transfer\"")));
+    }
+
+    #[test]
+    fn transfer_notifies_hook() {
+        let mut contract = new_contract();
+        contract.set_transfer_hook(Some(accounts(3)));
+        mint(&mut contract, "1", accounts(1));
+        transfer(&mut contract, accounts(1), accounts(2), "1");
+        assert!(has_function_call(&accounts(3), "on_nft_transferred"));
+    }
+
+    #[test]
+    fn admin_transfer_all_notifies_hook() {
+        let mut contract = new_contract();
+        contract.set_transfer_hook(Some(accounts(3)));
+        mint(&mut contract, "1", accounts(1));
+        set_context(accounts(0), 1);
+        contract.admin_transfer_all(accounts(1), accounts(2), None);
+        assert!(has_function_call(&accounts(3), "on_nft_transferred_batch"));
+        assert!(!has_function_call(&accounts(3), "on_nft_transferred"));
+    }
+
+    #[test]
+    fn admin_transfer_all_full_batch_fits_gas_with_hook() {
+        let mut contract = new_contract();
+        contract.set_transfer_hook(Some(accounts(3)));
+        for index in 0..MAX_ADMIN_TRANSFER_BATCH {
+            mint(&mut contract, &index.to_string(), accounts(1));
+        }
+        set_context(accounts(0), 1);
+        assert_eq!(
+            contract.admin_transfer_all(accounts(1), accounts(2), None),
+            MAX_ADMIN_TRANSFER_BATCH
+        );
+        let hook_calls = get_created_receipts()
+            .iter()
+            .filter(|receipt| receipt.receiver_id == accounts(3))
+            .count();
+        assert_eq!(hook_calls, 1);
+        // used gas includes the gas attached to the hook call
+        assert!(env::used_gas() < env::prepaid_gas());
+    }
+
+    #[test]
+    fn transfer_call_budgets_resolver_for_hook() {
+        let mut contract = new_contract();
+        contract.set_transfer_hook(Some(accounts(3)));
+        mint(&mut contract, "1", accounts(1));
+        set_context(accounts(1), 1);
+        contract.nft_transfer_call(accounts(2), "1".to_string(), None, None, "".to_string());
+        assert!(has_function_call(&accounts(3), "on_nft_transferred"));
+        let resolver_gas = get_created_receipts()
+            .iter()
+            .flat_map(|receipt| receipt.actions.iter())
+            .find_map(|action| match action {
+                VmAction::FunctionCall {
+                    function_name, gas, ..
+                } if function_name == "nft_resolve_transfer" => Some(*gas),
+                _ => None,
+            });
+        assert_eq!(resolver_gas, Some(GAS_FOR_RESOLVE_TRANSFER));
+    }
+
+    #[test]
+    fn admin_transfer_all_moves_tokens_in_pages() {
+        let mut contract = new_contract();
+        for token_id in ["1", "2", "3"] {
+            mint(&mut contract, token_id, accounts(1));
+        }
+        set_context(accounts(0), 1);
+        assert_eq!(
+            contract.admin_transfer_all(accounts(1), accounts(2), Some(2)),
+            2
+        );
+        assert_eq!(
+            contract.admin_transfer_all(accounts(1), accounts(2), None),
+            1
+        );
+        assert_eq!(
+            contract.admin_transfer_all(accounts(1), accounts(2), None),
+            0
+        );
+        for token_id in ["1", "2", "3"] {
+            assert_eq!(owner_of(&contract, token_id), Some(accounts(2)));
+        }
+    }
+
+    #[test]
+    fn admin_transfer_all_refunds_approvals() {
+        let mut contract = new_contract();
+        mint(&mut contract, "1", accounts(1));
+        set_context(accounts(1), APPROVAL_DEPOSIT);
+        contract.nft_approve("1".to_string(), accounts(3), None);
+        set_context(accounts(0), 1);
+        contract.admin_transfer_all(accounts(1), accounts(2), None);
+        assert!(!contract.nft_is_approved("1".to_string(), accounts(3), None));
+        assert!(get_created_receipts().iter().any(|receipt| {
+            receipt.receiver_id == accounts(1)
+                && receipt
+                    .actions
+                    .iter()
+                    .any(|action| matches!(action, VmAction::Transfer { deposit } if *deposit > 0))
+        }));
+    }
+
+    #[test]
+    fn approval_rate_limit_resets_at_window_boundary() {
+        let mut contract = new_contract();
+        mint(&mut contract, "1", accounts(1));
+        contract.set_approval_rate_limit(Some((1000, 2)));
+        set_context_at(accounts(1), APPROVAL_DEPOSIT, 0);
+        contract.nft_approve("1".to_string(), accounts(2), None);
+        contract.nft_approve("1".to_string(), accounts(3), None);
+        assert_eq!(contract.approvals_this_window(accounts(1)), 2);
+
+        set_context_at(accounts(1), APPROVAL_DEPOSIT, 1000);
+        assert_eq!(contract.approvals_this_window(accounts(1)), 0);
+        contract.nft_approve("1".to_string(), accounts(4), None);
+        assert_eq!(contract.approvals_this_window(accounts(1)), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Approval rate limit exceeded")]
+    fn approval_rate_limit_blocks_excess_approvals() {
+        let mut contract = new_contract();
+        mint(&mut contract, "1", accounts(1));
+        contract.set_approval_rate_limit(Some((1000, 2)));
+        set_context_at(accounts(1), APPROVAL_DEPOSIT, 0);
+        contract.nft_approve("1".to_string(), accounts(2), None);
+        contract.nft_approve("1".to_string(), accounts(3), None);
+        set_context_at(accounts(1), APPROVAL_DEPOSIT, 999);
+        contract.nft_approve("1".to_string(), accounts(4), None);
+    }
+
+    #[test]
+    fn storage_byte_cost_is_positive() {
+        let contract = new_contract();
+        assert!(contract.storage_byte_cost().0 > 0);
+    }
+
+    #[test]
+    fn vested_token_transfers_after_unlock() {
+        let mut contract = new_contract();
+        set_context(accounts(0), MINT_DEPOSIT);
+        contract.nft_mint_vested("1".to_string(), accounts(1), token_metadata("1"), 1000);
+        assert_eq!(contract.token_unlock("1".to_string()), Some(1000));
+        set_context_at(accounts(1), 1, 1000);
+        contract.nft_transfer(accounts(2), "1".to_string(), None, None);
+        assert_eq!(owner_of(&contract, "1"), Some(accounts(2)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Token is locked until 1000")]
+    fn vested_token_cannot_transfer_before_unlock() {
+        let mut contract = new_contract();
+        set_context(accounts(0), MINT_DEPOSIT);
+        contract.nft_mint_vested("1".to_string(), accounts(1), token_metadata("1"), 1000);
+        set_context_at(accounts(1), 1, 999);
+        contract.nft_transfer(accounts(2), "1".to_string(), None, None);
+    }
+
+    #[test]
+    fn locked_tokens_are_counted_per_owner() {
+        let mut contract = new_contract();
+        set_context(accounts(0), MINT_DEPOSIT);
+        contract.nft_mint_vested("1".to_string(), accounts(1), token_metadata("1"), 1000);
+        set_context(accounts(0), MINT_DEPOSIT);
+        contract.nft_mint_vested("2".to_string(), accounts(2), token_metadata("2"), 1000);
+        set_context(accounts(0), MINT_DEPOSIT);
+        contract.nft_mint_vested("3".to_string(), accounts(2), token_metadata("3"), 2000);
+        assert_eq!(
+            contract.locked_tokens_for(accounts(1)),
+            vec!["1".to_string()]
+        );
+        assert_eq!(contract.locked_tokens_for(accounts(2)).len(), 2);
+        assert_eq!(contract.locked_token_count(), 3);
+        set_context_at(accounts(0), 0, 1000);
+        assert_eq!(contract.locked_token_count(), 1);
+        assert_eq!(
+            contract.locked_tokens_for(accounts(2)),
+            vec!["3".to_string()]
+        );
+    }
+
+    #[test]
+    fn transfer_prunes_expired_lock() {
+        let mut contract = new_contract();
+        set_context(accounts(0), MINT_DEPOSIT);
+        contract.nft_mint_vested("1".to_string(), accounts(1), token_metadata("1"), 1000);
+        set_context_at(accounts(1), 1, 1000);
+        contract.nft_transfer(accounts(2), "1".to_string(), None, None);
+        assert_eq!(contract.token_unlock("1".to_string()), None);
+        assert!(contract.locked_by_owner.get(&accounts(1)).is_none());
+        assert!(contract.unlock_counts.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Token is soulbound")]
+    fn soulbound_batch_blocks_transfers() {
+        let mut contract = new_contract();
+        mint(&mut contract, "1", accounts(1));
+        mint(&mut contract, "2", accounts(1));
+        contract.set_soulbound_batch(vec!["1".to_string(), "2".to_string()], true);
+        assert!(contract.is_soulbound("2".to_string()));
+        transfer(&mut contract, accounts(1), accounts(2), "2");
+    }
+
+    #[test]
+    #[should_panic(expected = "Token is soulbound")]
+    fn rejected_transfer_leaves_state_untouched() {
+        let mut contract = new_contract();
+        mint(&mut contract, "1", accounts(1));
+        set_context(accounts(1), APPROVAL_DEPOSIT);
+        contract.nft_approve("1".to_string(), accounts(3), None);
+        set_context(accounts(0), 1);
+        contract.set_soulbound_batch(vec!["1".to_string()], true);
+        set_context(accounts(1), 1);
+        let storage_usage = env::storage_usage();
+        let rejection = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.nft_transfer(accounts(2), "1".to_string(), None, None)
+        }))
+        .unwrap_err();
+        assert_eq!(env::storage_usage(), storage_usage);
+        assert_eq!(owner_of(&contract, "1"), Some(accounts(1)));
+        assert!(contract.nft_is_approved("1".to_string(), accounts(3), None));
+        std::panic::resume_unwind(rejection);
+    }
+
+    #[test]
+    #[should_panic(expected = "Soulbound tokens are frozen")]
+    fn frozen_soulbound_set_cannot_change() {
+        let mut contract = new_contract();
+        mint(&mut contract, "1", accounts(1));
+        contract.freeze_soulbound();
+        contract.set_soulbound_batch(vec!["1".to_string()], true);
+    }
+
+    #[test]
+    fn mint_and_transfer_call_mints_then_calls_receiver() {
+        let mut contract = new_contract();
+        set_context(accounts(0), MINT_DEPOSIT);
+        contract.nft_mint_and_transfer_call(
+            "1".to_string(),
+            accounts(2),
+            token_metadata("1"),
+            "stake".to_string(),
+        );
+        assert_eq!(owner_of(&contract, "1"), Some(accounts(2)));
+        assert_eq!(contract.nft_minter("1".to_string()), Some(accounts(0)));
+        assert!(has_function_call(&accounts(2), "nft_on_transfer"));
+        assert!(has_function_call(&accounts(0), "nft_resolve_transfer"));
+    }
+
+    fn mint_pending(contract: &mut Contract, token_id: &str, receiver_id: AccountId) -> Token {
+        set_context(accounts(0), MINT_DEPOSIT);
+        contract.nft_mint_pending(token_id.to_string(), receiver_id, token_metadata(token_id))
+    }
+
+    #[test]
+    fn approve_pending_delivers_escrowed_token() {
+        let mut contract = new_contract();
+        mint_pending(&mut contract, "1", accounts(1));
+        mint_pending(&mut contract, "2", accounts(1));
+        assert_eq!(owner_of(&contract, "1"), Some(accounts(0)));
+        let mut pending = contract.pending_tokens_for(accounts(1));
+        pending.sort();
+        assert_eq!(pending, vec!["1".to_string(), "2".to_string()]);
+
+        contract.set_compliance_accounts(vec![accounts(4)]);
+        set_context(accounts(4), 0);
+        contract.approve_pending("1".to_string());
+        assert_eq!(owner_of(&contract, "1"), Some(accounts(1)));
+        assert_eq!(contract.get_pending_recipient("1".to_string()), None);
+        assert_eq!(
+            contract.pending_tokens_for(accounts(1)),
+            vec!["2".to_string()]
+        );
+        assert_eq!(contract.nft_supply_for_owner(accounts(0)), U128(1));
+    }
+
+    #[test]
+    fn reject_pending_burns_and_refunds_all_released_storage() {
+        let mut contract = new_contract();
+        mint_pending(&mut contract, "1", accounts(1));
+        set_context(accounts(0), 0);
+        let initial_storage_usage = env::storage_usage();
+        contract.reject_pending("1".to_string());
+        let released = (initial_storage_usage - env::storage_usage()) as u128;
+        assert_eq!(owner_of(&contract, "1"), None);
+        assert!(contract.pending_tokens_for(accounts(1)).is_empty());
+        assert!(get_created_receipts().iter().any(|receipt| {
+            receipt.receiver_id == accounts(0)
+                && matches!(
+                    receipt.actions[..],
+                    [VmAction::Transfer { deposit }] if deposit == released * env::storage_byte_cost()
+                )
+        }));
+    }
+
+    #[test]
+    #[should_panic(expected = "Token is pending compliance review")]
+    fn escrowed_token_cannot_be_transferred() {
+        let mut contract = new_contract();
+        mint_pending(&mut contract, "1", accounts(1));
+        transfer(&mut contract, accounts(0), accounts(2), "1");
+    }
+
+    #[test]
+    #[should_panic(expected = "Token is pending compliance review")]
+    fn escrowed_token_cannot_be_approved() {
+        let mut contract = new_contract();
+        mint_pending(&mut contract, "1", accounts(1));
+        set_context(accounts(0), APPROVAL_DEPOSIT);
+        contract.nft_approve("1".to_string(), accounts(2), None);
+    }
+
+    #[test]
+    fn admin_transfer_all_leaves_escrowed_tokens() {
+        let mut contract = new_contract();
+        mint_pending(&mut contract, "1", accounts(1));
+        mint(&mut contract, "2", accounts(0));
+        set_context(accounts(0), 1);
+        assert_eq!(
+            contract.admin_transfer_all(accounts(0), accounts(2), None),
+            1
+        );
+        assert_eq!(owner_of(&contract, "1"), Some(accounts(0)));
+        assert_eq!(owner_of(&contract, "2"), Some(accounts(2)));
+    }
+
+    #[test]
+    fn collection_description_round_trips() {
+        let mut contract = new_contract();
+        assert_eq!(contract.get_collection_description(), None);
+        contract.set_collection_description("Questions, on chain".to_string());
+        assert_eq!(
+            contract.get_collection_description(),
+            Some("Questions, on chain".to_string())
+        );
+    }
+
+    #[test]
+    fn token_detail_bundles_token_state() {
+        let mut contract = new_contract();
+        set_context(accounts(0), MINT_DEPOSIT);
+        contract.nft_mint_vested("1".to_string(), accounts(1), token_metadata("1"), 1000);
+        contract.set_soulbound_batch(vec!["1".to_string()], true);
+        let detail = contract.nft_token_detail("1".to_string()).unwrap();
+        assert_eq!(detail.owner_id, accounts(1));
+        assert_eq!(detail.metadata.unwrap().title, Some("1".to_string()));
+        assert_eq!(detail.approved_account_ids, Some(HashMap::new()));
+        assert!(detail.soulbound);
+        assert_eq!(detail.unlock_ns, Some(1000));
+        assert!(detail.locked);
+        assert!(contract.nft_token_detail("2".to_string()).is_none());
+    }
+
+    #[test]
+    fn bulk_configure_applies_only_given_fields() {
+        let mut contract = new_contract();
+        contract.set_require_reference_hash(true);
+        contract.bulk_configure(PartialConfig {
+            approval_status: Some("all".to_string()),
+            whitelist_window: None,
+            max_attached_deposit: Some(U128(5)),
+            require_reference_hash: None,
+            approval_rate_limit: None,
+            content_rating: None,
+        });
+        assert!(matches!(contract.mint_approval_status, Status::All));
+        assert_eq!(contract.get_max_attached_deposit(), Some(U128(5)));
+        assert!(contract.get_require_reference_hash());
+        assert_eq!(contract.get_whitelist_window(), None);
+        assert_eq!(contract.get_approval_rate_limit(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Window start must be before window end")]
+    fn bulk_configure_rejects_inverted_window() {
+        let mut contract = new_contract();
+        contract.bulk_configure(PartialConfig {
+            approval_status: None,
+            whitelist_window: Some((20, 10)),
+            max_attached_deposit: None,
+            require_reference_hash: None,
+            approval_rate_limit: None,
+            content_rating: None,
+        });
+    }
+
+    #[test]
+    fn vesting_tokens_lists_remaining_time() {
+        let mut contract = new_contract();
+        set_context_at(accounts(0), MINT_DEPOSIT, 100);
+        contract.nft_mint_vested("1".to_string(), accounts(1), token_metadata("1"), 1000);
+        contract.nft_mint_vested("2".to_string(), accounts(1), token_metadata("2"), 500);
+        set_context_at(accounts(0), 0, 400);
+        assert_eq!(
+            contract.nft_vesting_tokens(None, None),
+            vec![("1".to_string(), 600), ("2".to_string(), 100)]
+        );
+        assert_eq!(
+            contract.nft_vesting_tokens(Some(U128(1)), Some(1)),
+            vec![("2".to_string(), 100)]
+        );
+        set_context_at(accounts(0), 0, 500);
+        assert_eq!(
+            contract.nft_vesting_tokens(None, None),
+            vec![("1".to_string(), 500)]
+        );
+    }
+
+    #[test]
+    fn mint_requirements_deposit_covers_mint() {
+        let mut contract = new_contract();
+        contract.change_nft_approval_status("all".to_string());
+        let minter: AccountId = format!("{}.near", "m".repeat(59)).parse().unwrap();
+        for (token_id, color) in [("a", "red"), ("b", "blue")] {
+            let token_metadata = metadata_with_traits(&[
+                ("color", color),
+                ("size", token_id),
+                ("background", &format!("{}-{}", color, token_id)),
+            ]);
+            let requirements = contract.mint_requirements(minter.clone(), token_metadata.clone());
+            assert!(requirements.allowed);
+            set_context(minter.clone(), requirements.required_deposit.0);
+            contract.nft_mint(token_id.repeat(64), minter.clone(), token_metadata);
+        }
+        assert_eq!(contract.nft_supply_for_owner(minter), U128(2));
+    }
+
+    #[test]
+    fn pause_lifts_at_its_timestamp() {
+        let mut contract = new_contract();
+        set_context_at(accounts(0), 0, 100);
+        contract.set_paused_until(Some(1000));
+        assert!(contract.is_paused());
+        set_context_at(accounts(0), 0, 1000);
+        assert!(!contract.is_paused());
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract is paused")]
+    fn pause_blocks_transfers() {
+        let mut contract = new_contract();
+        mint(&mut contract, "1", accounts(1));
+        set_context_at(accounts(0), 0, 100);
+        contract.set_paused_until(Some(1000));
+        set_context_at(accounts(1), 1, 999);
+        contract.nft_transfer(accounts(2), "1".to_string(), None, None);
+    }
+
+    #[test]
+    fn minter_persists_after_transfer() {
+        let mut contract = new_contract();
+        set_context(accounts(0), 0);
+        contract.change_nft_approval_status("all".to_string());
+        set_context(accounts(1), MINT_DEPOSIT);
+        contract.nft_mint("1".to_string(), accounts(1), token_metadata("1"));
+        transfer(&mut contract, accounts(1), accounts(2), "1");
+        assert_eq!(contract.nft_minter("1".to_string()), Some(accounts(1)));
+        assert_eq!(owner_of(&contract, "1"), Some(accounts(2)));
+    }
+
+    #[test]
+    fn admin_events_use_configured_standard() {
+        let mut contract = new_contract();
+        contract.set_event_standard("qstn_ops".to_string());
+        contract.freeze_soulbound();
+        assert!(
+            get_logs()
+                .iter()
+                .any(|log| log.starts_with("EVENT_JSON:")
+                    && log.contains("\"standard\":\"qstn_ops\""))
+        );
+    }
+
+    #[test]
+    fn all_approvals_spans_tokens() {
+        let mut contract = new_contract();
+        mint(&mut contract, "1", accounts(1));
+        mint(&mut contract, "2", accounts(1));
+        set_context(accounts(1), APPROVAL_DEPOSIT);
+        contract.nft_approve("1".to_string(), accounts(2), None);
+        contract.nft_approve("2".to_string(), accounts(3), None);
+        let mut approvals = contract.all_approvals(None, None);
+        approvals.sort();
+        assert_eq!(
+            approvals,
+            vec![
+                ("1".to_string(), accounts(2), 1),
+                ("2".to_string(), accounts(3), 1)
+            ]
+        );
+        assert_eq!(contract.all_approvals(Some(U128(1)), Some(1)).len(), 1);
+        set_context(accounts(0), 1);
+        contract.set_batch_limits(BatchLimits {
+            approvals: 1,
+            ..contract.get_batch_limits()
+        });
+        assert_eq!(contract.all_approvals(None, None).len(), 1);
+    }
+
+    #[test]
+    fn status_change_allowed_after_cooldown() {
+        let mut contract = new_contract();
+        contract.set_status_change_cooldown(100);
+        set_context_at(accounts(0), 0, 1000);
+        contract.change_nft_approval_status("all".to_string());
+        set_context_at(accounts(0), 0, 1100);
+        contract.change_nft_approval_status("none".to_string());
+        assert_eq!(contract.get_last_status_change(), Some(1100));
+    }
+
+    #[test]
+    #[should_panic(expected = "Approval status was changed too recently")]
+    fn status_change_rejected_within_cooldown() {
+        let mut contract = new_contract();
+        contract.set_status_change_cooldown(100);
+        set_context_at(accounts(0), 0, 1000);
+        contract.change_nft_approval_status("all".to_string());
+        set_context_at(accounts(0), 0, 1099);
+        contract.change_nft_approval_status("none".to_string());
+    }
+
+    #[test]
+    fn compliance_config_lists_role_accounts() {
+        let mut contract = new_contract();
+        set_context(accounts(0), 1);
+        contract.set_compliance_accounts(vec![accounts(4)]);
+        let config = contract.get_compliance_config();
+        assert!(config.kyc_escrow);
+        assert!(!config.receiver_allowlist);
+        assert!(!config.require_registered);
+        assert_eq!(config.compliance_accounts, vec![accounts(4)]);
+    }
+
+    #[test]
+    fn dashboard_after_mints_and_burn() {
+        let mut contract = new_contract();
+        for token_id in ["1", "2", "3"] {
+            mint(&mut contract, token_id, accounts(1));
+        }
+        contract.internal_burn(&"2".to_string());
+        let dashboard = contract.get_dashboard();
+        assert_eq!(dashboard.total_minted, 3);
+        assert_eq!(dashboard.total_burned, 1);
+        assert_eq!(dashboard.current_supply, 2);
+        assert_eq!(dashboard.holder_count, 1);
+    }
+
+    #[test]
+    fn holder_count_follows_balances() {
+        let mut contract = new_contract();
+        mint(&mut contract, "1", accounts(1));
+        mint(&mut contract, "2", accounts(1));
+        assert_eq!(contract.get_dashboard().holder_count, 1);
+        transfer(&mut contract, accounts(1), accounts(2), "1");
+        assert_eq!(contract.get_dashboard().holder_count, 2);
+        transfer(&mut contract, accounts(1), accounts(2), "2");
+        assert_eq!(contract.get_dashboard().holder_count, 1);
+        set_context(accounts(0), 1);
+        contract.admin_transfer_all(accounts(2), accounts(3), None);
+        assert_eq!(contract.get_dashboard().holder_count, 1);
+        contract.internal_burn(&"1".to_string());
+        contract.internal_burn(&"2".to_string());
+        assert_eq!(contract.get_dashboard().holder_count, 0);
+    }
+
+    #[test]
+    fn snapshot_holders_in_pages() {
+        let mut contract = new_contract();
+        for (token_id, owner_id) in [("1", accounts(1)), ("2", accounts(2)), ("3", accounts(1))] {
+            mint(&mut contract, token_id, owner_id);
+        }
+        set_context(accounts(0), 0);
+        let first = contract.snapshot_holders(None, Some(2));
+        assert_eq!(first, vec![(accounts(1), U128(2)), (accounts(2), U128(1))]);
+        assert_eq!(contract.get_snapshot_progress(0), Some(U128(2)));
+
+        // a token minted before the cursor does not shift the next page
+        mint(&mut contract, "0", accounts(4));
+        set_context(accounts(0), 0);
+        assert!(contract.snapshot_holders(Some(U128(2)), Some(2)).is_empty());
+        assert_eq!(contract.get_snapshot_progress(0), Some(U128(3)));
+        assert_eq!(contract.latest_snapshot_id(), Some(0));
+
+        transfer(&mut contract, accounts(1), accounts(3), "1");
+        set_context(accounts(0), 0);
+        contract.snapshot_holders(None, None);
+        assert_eq!(contract.latest_snapshot_id(), Some(1));
+        assert_eq!(contract.get_snapshot(0, None, None).len(), 2);
+        let mut latest = contract.get_snapshot(1, None, None);
+        latest.sort();
+        assert_eq!(
+            latest,
+            vec![
+                (accounts(1), U128(1)),
+                (accounts(2), U128(1)),
+                (accounts(3), U128(1)),
+                (accounts(4), U128(1))
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Snapshot resumes at index 1")]
+    fn snapshot_rejects_stale_index() {
+        let mut contract = new_contract();
+        mint(&mut contract, "1", accounts(1));
+        mint(&mut contract, "2", accounts(2));
+        set_context(accounts(0), 0);
+        contract.snapshot_holders(None, Some(1));
+        contract.snapshot_holders(Some(U128(0)), Some(1));
+    }
+
+    #[test]
+    fn rarity_score_uses_current_supply() {
+        let mut contract = new_contract();
+        for (token_id, color) in [("1", "red"), ("2", "red"), ("3", "red"), ("4", "blue")] {
+            set_context(accounts(0), MINT_DEPOSIT);
+            contract.nft_mint(
+                token_id.to_string(),
+                accounts(1),
+                metadata_with_traits(&[("color", color)]),
+            );
+        }
+        contract.internal_burn(&"1".to_string());
+        assert_eq!(contract.compute_rarity_score("4".to_string()), Some(3000));
+        assert_eq!(contract.compute_rarity_score("2".to_string()), Some(1500));
+        assert_eq!(contract.compute_rarity_score("9".to_string()), None);
+    }
+
+    #[test]
+    fn supply_snapshot_balances() {
+        let mut contract = new_contract();
+        for token_id in ["1", "2", "3"] {
+            mint(&mut contract, token_id, accounts(1));
+        }
+        contract.internal_burn(&"2".to_string());
+        let snapshot = contract.supply_snapshot();
+        assert_eq!(snapshot.total_minted, 3);
+        assert_eq!(snapshot.total_burned, 1);
+        assert_eq!(
+            snapshot.total_minted - snapshot.total_burned,
+            snapshot.current_supply
+        );
+    }
+
+    #[test]
+    fn mint_before_deadline() {
+        let mut contract = new_contract();
+        contract.set_mint_deadline(Some(1000));
+        set_context_at(accounts(0), MINT_DEPOSIT, 999);
+        contract.nft_mint("1".to_string(), accounts(1), token_metadata("1"));
+        assert_eq!(owner_of(&contract, "1"), Some(accounts(1)));
+    }
+
+    #[test]
+    fn mint_after_deadline() {
+        let mut contract = new_contract();
+        contract.set_mint_deadline(Some(1000));
+        set_context_at(accounts(0), MINT_DEPOSIT, 1000);
+        let requirements = contract.mint_requirements(accounts(0), token_metadata("1"));
+        assert!(!requirements.allowed);
+        assert_eq!(
+            requirements.reason,
+            Some("Mint deadline passed".to_string())
+        );
+    }
+
+    #[test]
+    fn duplicate_whitelist_add_is_ignored_by_default() {
+        let mut contract = new_contract();
+        contract.add_whitelist_account(accounts(1));
+        assert!(contract.add_whitelist_account(accounts(1)));
+        assert_eq!(contract.whitelist_accounts, vec![accounts(1)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Account is already whitelisted")]
+    fn duplicate_whitelist_add_errors_under_error_policy() {
+        let mut contract = new_contract();
+        contract.set_duplicate_whitelist_policy(Policy::Error);
+        contract.add_whitelist_account(accounts(1));
+        contract.add_whitelist_account(accounts(1));
+    }
+
+    #[test]
+    fn supply_delta_across_blocks() {
+        let mut contract = new_contract();
+        testing_env!(context(accounts(0))
+            .attached_deposit(MINT_DEPOSIT)
+            .block_index(10)
+            .build());
+        contract.nft_mint("1".to_string(), accounts(1), token_metadata("1"));
+        testing_env!(context(accounts(0))
+            .attached_deposit(MINT_DEPOSIT)
+            .block_index(20)
+            .build());
+        contract.nft_mint("2".to_string(), accounts(1), token_metadata("2"));
+        contract.nft_mint("3".to_string(), accounts(1), token_metadata("3"));
+        testing_env!(context(accounts(0)).block_index(30).build());
+        contract.internal_burn(&"1".to_string());
+
+        let delta = contract.supply_delta(15);
+        assert_eq!((delta.minted, delta.burned), (2, 1));
+        let delta = contract.supply_delta(25);
+        assert_eq!((delta.minted, delta.burned), (0, 1));
+        let delta = contract.supply_delta(5);
+        assert_eq!((delta.minted, delta.burned), (3, 1));
+    }
+
+    #[test]
+    fn render_calls_renderer_or_returns_stored_metadata() {
+        let mut contract = new_contract();
+        mint(&mut contract, "1", accounts(1));
+        mint(&mut contract, "2", accounts(1));
+        contract.set_renderer("1".to_string(), Some(accounts(5)));
+        assert!(matches!(
+            contract.nft_render("1".to_string()),
+            PromiseOrValue::Promise(_)
+        ));
+        assert!(has_function_call(&accounts(5), "render"));
+        match contract.nft_render("2".to_string()) {
+            PromiseOrValue::Value(metadata) => {
+                assert_eq!(metadata.unwrap().title, Some("2".to_string()))
+            }
+            PromiseOrValue::Promise(_) => panic!("token without renderer must not call out"),
+        }
+    }
+
+    #[test]
+    fn export_references_matches_token_metadata() {
+        let mut contract = new_contract();
+        set_context(accounts(0), MINT_DEPOSIT);
+        contract.nft_mint(
+            "1".to_string(),
+            accounts(1),
+            TokenMetadata {
+                reference: Some("ipfs://one".to_string()),
+                reference_hash: Some(Base64VecU8(vec![1, 2, 3])),
+                ..token_metadata("1")
+            },
+        );
+        contract.nft_mint("2".to_string(), accounts(1), token_metadata("2"));
+        assert_eq!(
+            contract.export_references(None, Some(2)),
+            vec![
+                (
+                    "1".to_string(),
+                    Some("ipfs://one".to_string()),
+                    Some(vec![1, 2, 3])
+                ),
+                ("2".to_string(), None, None)
+            ]
+        );
+        set_context(accounts(0), 1);
+        contract.set_batch_limits(BatchLimits {
+            references: 1,
+            ..contract.get_batch_limits()
+        });
+        assert_eq!(contract.export_references(None, None).len(), 1);
+    }
+
+    #[test]
+    fn verify_reference_hash_compares_content() {
+        let mut contract = new_contract();
+        set_context(accounts(0), MINT_DEPOSIT);
+        contract.nft_mint(
+            "1".to_string(),
+            accounts(1),
+            TokenMetadata {
+                reference: Some("ipfs://one".to_string()),
+                reference_hash: Some(Base64VecU8(env::sha256(b"content"))),
+                ..token_metadata("1")
+            },
+        );
+        contract.nft_mint("2".to_string(), accounts(1), token_metadata("2"));
+        assert!(contract.verify_reference_hash("1".to_string(), b"content".to_vec()));
+        assert!(!contract.verify_reference_hash("1".to_string(), b"other".to_vec()));
+        assert!(!contract.verify_reference_hash("2".to_string(), b"content".to_vec()));
+    }
+
+    #[test]
+    fn paused_owner_minting_is_gated_by_status() {
+        let mut contract = new_contract();
+        assert!(
+            contract
+                .mint_requirements(accounts(0), token_metadata("1"))
+                .allowed
+        );
+        contract.pause_owner_minting();
+        assert_eq!(
+            contract
+                .mint_requirements(accounts(0), token_metadata("1"))
+                .reason,
+            Some("Minting is not allowed for Now".to_string())
+        );
+    }
+
+    #[test]
+    fn batch_at_limit_succeeds() {
+        let mut contract = new_contract();
+        for token_id in ["1", "2"] {
+            mint(&mut contract, token_id, accounts(1));
+        }
+        contract.set_batch_limits(BatchLimits {
+            soulbound: 2,
+            ..contract.get_batch_limits()
+        });
+        contract.set_soulbound_batch(vec!["1".to_string(), "2".to_string()], true);
+        assert!(contract.is_soulbound("2".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Batch exceeds maximum of 2 tokens")]
+    fn batch_over_limit_is_rejected() {
+        let mut contract = new_contract();
+        for token_id in ["1", "2", "3"] {
+            mint(&mut contract, token_id, accounts(1));
+        }
+        contract.set_batch_limits(BatchLimits {
+            soulbound: 2,
+            ..contract.get_batch_limits()
+        });
+        contract.set_soulbound_batch(
+            vec!["1".to_string(), "2".to_string(), "3".to_string()],
+            true,
+        );
+    }
+
+    #[test]
+    fn mint_in_folder_composes_urls() {
+        let mut contract = new_contract();
+        set_context(accounts(0), MINT_DEPOSIT);
+        let token = contract.nft_mint_in_folder(
+            "7".to_string(),
+            accounts(1),
+            "genesis".to_string(),
+            token_metadata("7"),
+        );
+        let metadata = token.metadata.unwrap();
+        assert_eq!(metadata.media, Some("genesis/7".to_string()));
+        assert_eq!(metadata.reference, Some("genesis/7".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid folder name")]
+    fn mint_in_folder_rejects_nested_folder() {
+        let mut contract = new_contract();
+        set_context(accounts(0), MINT_DEPOSIT);
+        contract.nft_mint_in_folder(
+            "7".to_string(),
+            accounts(1),
+            "a/b".to_string(),
+            token_metadata("7"),
+        );
+    }
+
+    #[test]
+    fn last_mint_gas_grows_with_metadata() {
+        let mut contract = new_contract();
+        mint(&mut contract, "1", accounts(1));
+        let small = contract.debug_last_mint_gas();
+        set_context(accounts(0), MINT_DEPOSIT);
+        contract.nft_mint(
+            "2".to_string(),
+            accounts(1),
+            TokenMetadata {
+                description: Some("a".repeat(4000)),
+                ..token_metadata("2")
+            },
+        );
+        assert!(small > 0);
+        assert!(contract.debug_last_mint_gas() > small);
+    }
+
+    #[test]
+    fn fallback_metadata_fills_missing_media() {
+        let mut contract = new_contract();
+        mint(&mut contract, "1", accounts(1));
+        contract.set_fallback_metadata(Some(TokenMetadata {
+            media: Some("ipfs://placeholder".to_string()),
+            ..token_metadata("fallback")
+        }));
+        let metadata = contract
+            .nft_token("1".to_string())
+            .unwrap()
+            .metadata
+            .unwrap();
+        assert_eq!(metadata.media, Some("ipfs://placeholder".to_string()));
+        assert_eq!(metadata.title, Some("1".to_string()));
+    }
+
+    #[test]
+    fn fallback_metadata_applies_to_token_lists() {
+        let mut contract = new_contract();
+        mint(&mut contract, "1", accounts(1));
+        contract.set_fallback_metadata(Some(TokenMetadata {
+            media: Some("ipfs://placeholder".to_string()),
+            ..token_metadata("fallback")
+        }));
+        let owned = contract.nft_tokens_for_owner(accounts(1), None, None);
+        let listed = contract.nft_tokens(None, None);
+        for tokens in [owned, listed] {
+            assert_eq!(
+                tokens[0].metadata.as_ref().unwrap().media,
+                Some("ipfs://placeholder".to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn transfer_clear_approvals_refunds_previous_owner() {
+        let mut contract = new_contract();
+        mint(&mut contract, "1", accounts(1));
+        set_context(accounts(1), APPROVAL_DEPOSIT);
+        contract.nft_approve("1".to_string(), accounts(2), None);
+        set_context(accounts(1), 1);
+        contract.nft_transfer_clear_approvals("1".to_string(), accounts(3), None);
+        assert_eq!(owner_of(&contract, "1"), Some(accounts(3)));
+        assert!(!contract.nft_is_approved("1".to_string(), accounts(2), None));
+        assert!(get_created_receipts().iter().any(|receipt| {
+            receipt.receiver_id == accounts(1)
+                && matches!(receipt.actions[..], [VmAction::Transfer { .. }])
+        }));
+    }
+
+    #[test]
+    fn tokens_minted_by_each_account() {
+        let mut contract = new_contract();
+        contract.change_nft_approval_status("all".to_string());
+        mint(&mut contract, "1", accounts(1));
+        set_context(accounts(1), MINT_DEPOSIT);
+        contract.nft_mint("2".to_string(), accounts(2), token_metadata("2"));
+        contract.nft_mint("3".to_string(), accounts(2), token_metadata("3"));
+        transfer(&mut contract, accounts(2), accounts(3), "2");
+        assert_eq!(
+            contract.nft_tokens_minted_by(accounts(0), None, None),
+            vec!["1".to_string()]
+        );
+        assert_eq!(
+            contract.nft_tokens_minted_by(accounts(1), None, None),
+            vec!["2".to_string(), "3".to_string()]
+        );
+        assert_eq!(
+            contract.nft_tokens_minted_by(accounts(1), Some(U128(1)), Some(1)),
+            vec!["3".to_string()]
+        );
+    }
+
+    #[test]
+    fn oracle_unlocks_token() {
+        let mut contract = new_contract();
+        set_context(accounts(0), MINT_DEPOSIT);
+        contract.nft_mint_vested("1".to_string(), accounts(1), token_metadata("1"), u64::MAX);
+        contract.set_oracle(Some(accounts(5)));
+        set_context(accounts(5), 0);
+        contract.unlock_token_by_oracle("1".to_string());
+        assert_eq!(contract.token_unlock("1".to_string()), None);
+        transfer(&mut contract, accounts(1), accounts(2), "1");
+        assert_eq!(owner_of(&contract, "1"), Some(accounts(2)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the oracle can unlock tokens")]
+    fn non_oracle_cannot_unlock_token() {
+        let mut contract = new_contract();
+        set_context(accounts(0), MINT_DEPOSIT);
+        contract.nft_mint_vested("1".to_string(), accounts(1), token_metadata("1"), u64::MAX);
+        contract.set_oracle(Some(accounts(5)));
+        set_context(accounts(1), 0);
+        contract.unlock_token_by_oracle("1".to_string());
+    }
+
+    #[test]
+    fn tokens_chrono_follows_mint_order() {
+        let mut contract = new_contract();
+        for token_id in ["c", "a", "b"] {
+            mint(&mut contract, token_id, accounts(1));
+        }
+        transfer(&mut contract, accounts(1), accounts(2), "a");
+        contract.internal_burn(&"b".to_string());
+        assert_eq!(
+            contract.nft_tokens_chrono(None, None),
+            vec![
+                ("c".to_string(), accounts(1)),
+                ("a".to_string(), accounts(2))
+            ]
+        );
+        assert_eq!(
+            contract.nft_tokens_chrono(Some(U128(1)), Some(1)),
+            vec![("a".to_string(), accounts(2))]
+        );
+    }
+
+    #[test]
+    fn media_batch_updates_each_token() {
+        let mut contract = new_contract();
+        for token_id in ["1", "2", "3"] {
+            mint(&mut contract, token_id, accounts(1));
+        }
+        set_context(accounts(0), MINT_DEPOSIT);
+        contract.set_media_batch(
+            ["1", "2", "3"]
+                .iter()
+                .map(|token_id| (token_id.to_string(), format!("ipfs://cid/{}", token_id)))
+                .collect(),
+        );
+        for token_id in ["1", "2", "3"] {
+            let metadata = contract
+                .nft_token(token_id.to_string())
+                .unwrap()
+                .metadata
+                .unwrap();
+            assert_eq!(metadata.media, Some(format!("ipfs://cid/{}", token_id)));
+        }
+    }
+
+    #[test]
+    fn deferred_metadata_is_assigned_later() {
+        let mut contract = new_contract();
+        set_context(accounts(0), MINT_DEPOSIT);
+        contract.nft_mint_deferred("1".to_string(), accounts(1));
+        assert!(contract.is_metadata_deferred("1".to_string()));
+        contract.assign_deferred_metadata(vec!["1".to_string()], vec![token_metadata("revealed")]);
+        assert!(!contract.is_metadata_deferred("1".to_string()));
+        let metadata = contract
+            .nft_token("1".to_string())
+            .unwrap()
+            .metadata
+            .unwrap();
+        assert_eq!(metadata.title, Some("revealed".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Token has no deferred metadata: 1")]
+    fn deferred_metadata_is_assigned_once() {
+        let mut contract = new_contract();
+        set_context(accounts(0), MINT_DEPOSIT);
+        contract.nft_mint_deferred("1".to_string(), accounts(1));
+        contract.assign_deferred_metadata(vec!["1".to_string()], vec![token_metadata("a")]);
+        contract.assign_deferred_metadata(vec!["1".to_string()], vec![token_metadata("b")]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Ownership has been renounced")]
+    fn renounced_owner_cannot_configure() {
+        let mut contract = new_contract();
+        contract.renounce_ownership();
+        assert!(contract.is_ownership_renounced());
+        contract.set_paused_until(Some(1000));
+    }
+
+    #[test]
+    fn renounced_owner_loses_mint_bypass() {
+        let mut contract = new_contract();
+        contract.renounce_ownership();
+        assert_eq!(
+            contract
+                .mint_requirements(accounts(0), token_metadata("1"))
+                .reason,
+            Some("Minting is not allowed for Now".to_string())
+        );
+    }
+
+    #[test]
+    fn frozen_field_leaves_other_fields_editable() {
+        let mut contract = new_contract();
+        mint(&mut contract, "1", accounts(1));
+        contract.freeze_field("extra".to_string());
+        assert_eq!(contract.get_frozen_fields(), vec!["extra".to_string()]);
+        set_context(accounts(0), MINT_DEPOSIT);
+        contract.set_media_batch(vec![("1".to_string(), "ipfs://cid".to_string())]);
+        let metadata = contract
+            .nft_token("1".to_string())
+            .unwrap()
+            .metadata
+            .unwrap();
+        assert_eq!(metadata.media, Some("ipfs://cid".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown metadata field: colour")]
+    fn freeze_field_rejects_unknown_field() {
+        let mut contract = new_contract();
+        contract.freeze_field("colour".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Metadata field is frozen: media")]
+    fn frozen_media_rejects_media_updates() {
+        let mut contract = new_contract();
+        mint(&mut contract, "1", accounts(1));
+        contract.freeze_field("media".to_string());
+        set_context(accounts(0), MINT_DEPOSIT);
+        contract.set_media_batch(vec![("1".to_string(), "ipfs://cid".to_string())]);
+    }
+
+    #[test]
+    fn exist_batch_flags_live_ids() {
+        let mut contract = new_contract();
+        mint(&mut contract, "1", accounts(1));
+        mint(&mut contract, "2", accounts(1));
+        contract.internal_burn(&"2".to_string());
+        assert_eq!(
+            contract.nft_exist_batch(vec!["1".to_string(), "2".to_string(), "3".to_string()]),
+            vec![true, false, false]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Burned token id can be reused from 1100")]
+    fn burned_id_cannot_be_reminted_within_grace_period() {
+        let mut contract = new_contract();
+        contract.set_allow_reuse_after_burn(Some(100));
+        mint(&mut contract, "1", accounts(1));
+        set_context_at(accounts(0), 0, 1000);
+        contract.internal_burn(&"1".to_string());
+        assert_eq!(contract.get_burned_at("1".to_string()), Some(1000));
+        set_context_at(accounts(0), MINT_DEPOSIT, 1099);
+        contract.nft_mint("1".to_string(), accounts(1), token_metadata("1"));
+    }
+
+    #[test]
+    fn burned_id_is_reminted_after_grace_period() {
+        let mut contract = new_contract();
+        contract.set_allow_reuse_after_burn(Some(100));
+        mint(&mut contract, "1", accounts(1));
+        set_context_at(accounts(0), 0, 1000);
+        contract.internal_burn(&"1".to_string());
+        set_context_at(accounts(0), MINT_DEPOSIT, 1100);
+        contract.nft_mint("1".to_string(), accounts(2), token_metadata("1"));
+        assert_eq!(owner_of(&contract, "1"), Some(accounts(2)));
+        assert_eq!(contract.get_burned_at("1".to_string()), None);
+        assert_eq!(contract.mint_order.len(), 1);
+    }
+
+    #[test]
+    fn reminted_id_moves_to_new_minter() {
+        let mut contract = new_contract();
+        contract.set_allow_reuse_after_burn(Some(0));
+        contract.change_nft_approval_status("all".to_string());
+        mint(&mut contract, "1", accounts(1));
+        contract.internal_burn(&"1".to_string());
+        assert_eq!(contract.nft_minter("1".to_string()), Some(accounts(0)));
+        set_context(accounts(2), MINT_DEPOSIT);
+        contract.nft_mint("1".to_string(), accounts(2), token_metadata("1"));
+        assert_eq!(contract.nft_minter("1".to_string()), Some(accounts(2)));
+        assert!(contract
+            .nft_tokens_minted_by(accounts(0), None, None)
+            .is_empty());
+        assert_eq!(
+            contract.nft_tokens_minted_by(accounts(2), None, None),
+            vec!["1".to_string()]
+        );
+
+        // reminted by the original minter, the id is listed once
+        contract.internal_burn(&"1".to_string());
+        set_context(accounts(2), MINT_DEPOSIT);
+        contract.nft_mint("1".to_string(), accounts(2), token_metadata("1"));
+        assert_eq!(
+            contract.nft_tokens_minted_by(accounts(2), None, None),
+            vec!["1".to_string()]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Burned token id cannot be reused")]
+    fn burned_id_is_never_reminted_without_grace_period() {
+        let mut contract = new_contract();
+        mint(&mut contract, "1", accounts(1));
+        contract.internal_burn(&"1".to_string());
+        mint(&mut contract, "1", accounts(1));
+    }
+
+    #[test]
+    fn content_rating_with_token_override() {
+        let mut contract = new_contract();
+        mint(&mut contract, "1", accounts(1));
+        mint(&mut contract, "2", accounts(1));
+        contract.set_content_rating(Some("safe".to_string()));
+        contract.set_token_content_rating("2".to_string(), Some("mature".to_string()));
+        assert_eq!(contract.get_content_rating(), Some("safe".to_string()));
+        assert_eq!(
+            contract.get_token_content_rating("1".to_string()),
+            Some("safe".to_string())
+        );
+        assert_eq!(
+            contract
+                .nft_token_detail("2".to_string())
+                .unwrap()
+                .content_rating,
+            Some("mature".to_string())
+        );
+        contract.set_token_content_rating("2".to_string(), None);
+        assert_eq!(
+            contract.get_token_content_rating("2".to_string()),
+            Some("safe".to_string())
+        );
+    }
+
+    #[test]
+    fn test_migrate_from_original_layout() {
+        set_context(accounts(0), 3 * MINT_DEPOSIT);
+        let mut tokens = NonFungibleToken::new(
+            StorageKey::NonFungibleToken,
+            accounts(0),
+            Some(StorageKey::TokenMetadata),
+            Some(StorageKey::Enumeration),
+            Some(StorageKey::Approval),
+        );
+        tokens.internal_mint("1".to_string(), accounts(1), Some(token_metadata("one")));
+        tokens.internal_mint("2".to_string(), accounts(1), Some(token_metadata("two")));
+        tokens.internal_mint("3".to_string(), accounts(2), Some(token_metadata("three")));
+        env::state_write(&BaseState {
+            tokens,
+            metadata: LazyOption::new(StorageKey::Metadata, None),
+            mint_approval_status: Status::Whitelist,
+            whitelist_accounts: vec![accounts(3)],
+        });
+
+        let contract = Contract::migrate();
+        assert_eq!(contract.nft_total_supply(), U128(3));
+        assert_eq!(contract.total_minted, 3);
+        assert_eq!(contract.total_burned, 0);
+        assert_eq!(contract.holder_count, 2);
+        let token = contract.nft_token("3".to_string()).unwrap();
+        assert_eq!(token.owner_id, accounts(2));
+        assert_eq!(token.metadata.unwrap().title, Some("three".to_string()));
+        assert!(matches!(contract.mint_approval_status, Status::Whitelist));
+        assert_eq!(contract.whitelist_accounts, vec![accounts(3)]);
+        assert_eq!(contract.batch_limits.snapshot, MAX_SNAPSHOT_BATCH);
+    }
+}